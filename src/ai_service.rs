@@ -1,27 +1,57 @@
 use async_trait::async_trait;
-use dialoguer::{Confirm, Select};
 use log::{debug, info, warn};
 use crate::config::{AIService, Config, AIServiceConfig};
+use crate::glossary;
+use crate::prompt;
+use crate::language::{self, LanguagePair};
+use crate::mask;
 use crate::terminal_format::print_progress;
+use crate::tm;
+use crate::wrap;
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Message {
-    pub role: String,
-    pub content: String,
-}
 use copilot_client::CopilotClient;
 
 #[async_trait]
 pub trait AiService: Send + Sync {
-    async fn translate(&self, text: &str, direction: &crate::config::TranslateDirection) -> anyhow::Result<String> {
+    async fn translate(&self, text: &str, direction: &LanguagePair) -> anyhow::Result<String> {
+        self.translate_as(text, direction, TextKind::CommitMessage).await
+    }
+
+    async fn translate_as(&self, text: &str, direction: &LanguagePair, kind: TextKind) -> anyhow::Result<String> {
+        // 代码、路径、URL 和引号中的内容替换为占位符后再翻译，译文中的占位符不完整时重试
+        let masked = mask::mask(text);
+        if masked.has_placeholders() {
+            let system_prompt = get_translation_prompt(&masked.text, direction, true, kind);
+            for attempt in 1..=MASK_ATTEMPTS {
+                let translated = self.chat(&system_prompt, &masked.text).await?;
+                match masked.restore(&translated) {
+                    Ok(restored) => return Ok(restored),
+                    Err(e) => warn!("第 {} 次翻译未能保留全部占位符: {}", attempt, e),
+                }
+            }
+            warn!("多次翻译均未能保留全部占位符，改为直接翻译原文");
+        }
+
         // 使用翻译的 prompt
-        let system_prompt = get_translation_prompt(text, direction);
+        let system_prompt = get_translation_prompt(text, direction, false, kind);
         Ok(self.chat(&system_prompt, text).await?)
     }
 
     async fn chat(&self, system_prompt: &str, user_content: &str) -> anyhow::Result<String>;
 }
 
+/// 待翻译文本的类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextKind {
+    /// 提交信息：按提交信息格式翻译，并记录到翻译记忆
+    CommitMessage,
+    /// 文档片段：保持原有的换行和标记，不使用翻译记忆
+    Document,
+}
+
+/// 翻译结果中的占位符不完整时，使用占位符翻译的最多次数
+const MASK_ATTEMPTS: usize = 2;
+
 pub use AiService as Translator; // 为了兼容性，保留原有的 Translator 类型
 
 pub struct DeepSeekTranslator {
@@ -191,38 +221,40 @@ impl QwenTranslator {
     }
 }
 
-// 添加一个新的工具函数
-fn wrap_chinese_text(text: &str, max_width: usize) -> String {
-    let mut result = String::new();
-    let mut current_line = String::new();
-    let mut current_width = 0;
-
-    for c in text.chars() {
-        let char_width = if c.is_ascii() { 1 } else { 2 };
-
-        if current_width + char_width > max_width {
-            result.push_str(&current_line);
-            result.push('\n');
-            current_line.clear();
-            current_width = 0;
-        }
+/// 原文中的代码等内容已替换为占位符时附加的要求
+const PLACEHOLDER_RULE: &str = "Placeholders such as ⟦0⟧ stand for code, file paths, URLs or quoted text. Copy every placeholder into the translation exactly as written, exactly once, and never translate, renumber or remove them.";
 
-        current_line.push(c);
-        current_width += char_width;
+fn get_translation_prompt(text: &str, direction: &LanguagePair, masked: bool, kind: TextKind) -> String {
+    // 术语表中原文出现的术语、翻译记忆中的参考译文和占位符说明，放在待翻译文本之前
+    let mut sections: Vec<String> = glossary::current().translation_prompt(text, direction).into_iter().collect();
+    if kind == TextKind::CommitMessage {
+        sections.extend(tm::current().lock().ok().and_then(|memory| memory.reference_prompt(text, direction)));
     }
-
-    if !current_line.is_empty() {
-        result.push_str(&current_line);
+    if masked {
+        sections.push(PLACEHOLDER_RULE.to_string());
     }
-
-    result
-}
-
-fn get_translation_prompt(text: &str, direction: &crate::config::TranslateDirection) -> String {
-    use crate::config::TranslateDirection;
-    
-    let prompt = match direction {
-        TranslateDirection::ChineseToEnglish => format!(
+    // 各部分之间空一行，并与提示词模板中的行保持相同的缩进
+    let context: String = sections.iter()
+        .map(|section| format!("{}\n\n    ", section.replace('\n', "\n    ")))
+        .collect();
+    let prompt = match (kind, language::primary(&direction.source), language::primary(&direction.target)) {
+        (TextKind::Document, _, _) => {
+            let source = language::english_name(&direction.source);
+            let target = language::english_name(&direction.target);
+            format!(
+                r#"You are a professional translator. Please translate the following {source} document text to {target}.
+    Important rules:
+    1. Keep code identifiers, file paths, numbers and URLs unchanged
+    2. Keep Markdown syntax, HTML tags and format specifiers such as %s or {{0}} unchanged
+    3. Keep the original line breaks, DO NOT wrap or join lines
+    4. Only return the {target} translation, DO NOT include the original {source} text
+    5. Keep the meaning accurate, no need to rewrite or expand the content
+
+    {context}Text to translate:
+    {text}"#
+            )
+        }
+        (_, "zh", "en") => format!(
             r#"You are a professional translator. Please translate the following Chinese text to English.
     Important rules:
     1. Keep all English content, numbers, and English punctuation unchanged
@@ -238,11 +270,12 @@ fn get_translation_prompt(text: &str, direction: &crate::config::TranslateDirect
     2. Add plugin configuration interface
     3. Setup plugin discovery path: "/插件"
 
-    Text to translate:
+    {}Text to translate:
     {}"#,
-            wrap_chinese_text(text, 72)
+            context,
+            wrap::wrap_text(text, wrap::MAX_LINE_WIDTH)
         ),
-        TranslateDirection::EnglishToChinese => format!(
+        (_, "en", "zh") if language::is_simplified_chinese(&direction.target) => format!(
             r#"You are a professional translator. Please translate the following English text to Chinese.
     Important rules:
     1. Keep all Chinese content, numbers, and Chinese punctuation unchanged
@@ -259,10 +292,28 @@ fn get_translation_prompt(text: &str, direction: &crate::config::TranslateDirect
     2. 添加插件配置接口
     3. 设置插件发现路径: "/plugins"
 
-    Text to translate:
+    {}Text to translate:
     {}"#,
+            context,
             text
         ),
+        _ => {
+            let source = language::english_name(&direction.source);
+            let target = language::english_name(&direction.target);
+            format!(
+                r#"You are a professional translator. Please translate the following {source} text to {target}.
+    Important rules:
+    1. Keep code identifiers, file paths, numbers and URLs unchanged
+    2. Do not translate any content inside quotes
+    3. Keep the commit type prefix (such as "feat:") and the "Log:"/"Influence:" field names unchanged
+    4. Maintain the original text structure and formatting
+    5. Only return the {target} translation, DO NOT include the original {source} text
+    6. Keep simple and concise, no need to rewrite or expand the content
+
+    {context}Text to translate:
+    {text}"#
+            )
+        }
     };
 
     debug!("生成的提示词:\n{}", prompt);
@@ -295,10 +346,7 @@ impl AiService for DeepSeekTranslator {
             "max_tokens": self.max_tokens
         });
 
-        let ai_host = match url.split('/').nth(2) {
-            Some(host) => host,
-            None => "api.deepseek.com",
-        };
+        let ai_host = url.split('/').nth(2).unwrap_or("api.deepseek.com");
         print_progress(&format!("正在请求 {} 进行AI对话", ai_host), None);
 
         loop {
@@ -330,10 +378,8 @@ impl AiService for DeepSeekTranslator {
                 }
                 Err(e) if e.is_timeout() => {
                     warn!("请求超时: {}", e);
-                    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-                        .with_prompt("请求超时，是否重试？")
-                        .default(true)
-                        .interact()? {
+                    // 非交互模式下不重试，避免无限等待
+                    if prompt::is_non_interactive() || !prompt::confirm("请求超时，是否重试？", true)? {
                         return Err(anyhow::anyhow!("请求超时"));
                     }
                     continue;
@@ -370,10 +416,7 @@ impl AiService for OpenAITranslator {
             "max_tokens": self.max_tokens
         });
 
-        let ai_host = match url.split('/').nth(2) {
-            Some(host) => host,
-            None => "api.openai.com",
-        };
+        let ai_host = url.split('/').nth(2).unwrap_or("api.openai.com");
         print_progress(&format!("正在请求 {} 进行AI对话", ai_host), None);
 
         loop {
@@ -405,10 +448,8 @@ impl AiService for OpenAITranslator {
                 }
                 Err(e) if e.is_timeout() => {
                     warn!("请求超时: {}", e);
-                    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-                        .with_prompt("请求超时，是否重试？")
-                        .default(true)
-                        .interact()? {
+                    // 非交互模式下不重试，避免无限等待
+                    if prompt::is_non_interactive() || !prompt::confirm("请求超时，是否重试？", true)? {
                         return Err(anyhow::anyhow!("请求超时"));
                     }
                     continue;
@@ -445,10 +486,7 @@ impl AiService for ClaudeTranslator {
             "max_tokens": self.max_tokens
         });
 
-        let ai_host = match url.split('/').nth(2) {
-            Some(host) => host,
-            None => "api.anthropic.com",
-        };
+        let ai_host = url.split('/').nth(2).unwrap_or("api.anthropic.com");
         print_progress(&format!("正在请求 {} 进行AI对话", ai_host), None);
 
         loop {
@@ -481,10 +519,8 @@ impl AiService for ClaudeTranslator {
                 }
                 Err(e) if e.is_timeout() => {
                     warn!("请求超时: {}", e);
-                    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-                        .with_prompt("请求超时，是否重试？")
-                        .default(true)
-                        .interact()? {
+                    // 非交互模式下不重试，避免无限等待
+                    if prompt::is_non_interactive() || !prompt::confirm("请求超时，是否重试？", true)? {
                         return Err(anyhow::anyhow!("请求超时"));
                     }
                     continue;
@@ -515,7 +551,7 @@ impl AiService for CopilotTranslator {
         debug!("发送给 Copilot 的消息:\n{}", serde_json::to_string_pretty(&messages)?);
         let response = self.client.chat_completion(messages, self.model.clone()).await?;
         print_progress(&format!("正在请求 {} 进行AI对话", ai_host), Some(100));
-        let result = response.choices.get(0)
+        let result = response.choices.first()
             .map(|choice| choice.message.content.clone())
             .unwrap_or_default();
         Ok(result)
@@ -544,10 +580,7 @@ impl AiService for GeminiTranslator {
             }
         });
 
-        let ai_host = match url.split('/').nth(2) {
-            Some(host) => host,
-            None => "generativelanguage.googleapis.com",
-        };
+        let ai_host = url.split('/').nth(2).unwrap_or("generativelanguage.googleapis.com");
         print_progress(&format!("正在请求 {} 进行AI对话", ai_host), None);
 
         loop {
@@ -578,10 +611,8 @@ impl AiService for GeminiTranslator {
                 }
                 Err(e) if e.is_timeout() => {
                     warn!("请求超时: {}", e);
-                    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-                        .with_prompt("请求超时，是否重试？")
-                        .default(true)
-                        .interact()? {
+                    // 非交互模式下不重试，避免无限等待
+                    if prompt::is_non_interactive() || !prompt::confirm("请求超时，是否重试？", true)? {
                         return Err(anyhow::anyhow!("请求超时"));
                     }
                     continue;
@@ -618,10 +649,7 @@ impl AiService for GrokTranslator {
             "max_tokens": self.max_tokens
         });
 
-        let ai_host = match url.split('/').nth(2) {
-            Some(host) => host,
-            None => "api.x.ai",
-        };
+        let ai_host = url.split('/').nth(2).unwrap_or("api.x.ai");
         print_progress(&format!("正在请求 {} 进行AI对话", ai_host), None);
 
         loop {
@@ -653,10 +681,8 @@ impl AiService for GrokTranslator {
                 }
                 Err(e) if e.is_timeout() => {
                     warn!("请求超时: {}", e);
-                    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-                        .with_prompt("请求超时，是否重试？")
-                        .default(true)
-                        .interact()? {
+                    // 非交互模式下不重试，避免无限等待
+                    if prompt::is_non_interactive() || !prompt::confirm("请求超时，是否重试？", true)? {
                         return Err(anyhow::anyhow!("请求超时"));
                     }
                     continue;
@@ -694,10 +720,7 @@ impl AiService for QwenTranslator {
             "max_tokens": self.max_tokens
         });
 
-        let ai_host = match url.split('/').nth(2) {
-            Some(host) => host,
-            None => "dashscope.aliyuncs.com",
-        };
+        let ai_host = url.split('/').nth(2).unwrap_or("dashscope.aliyuncs.com");
         print_progress(&format!("正在请求 {} 进行AI对话", ai_host), None);
 
         loop {
@@ -729,10 +752,8 @@ impl AiService for QwenTranslator {
                 }
                 Err(e) if e.is_timeout() => {
                     warn!("请求超时: {}", e);
-                    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-                        .with_prompt("请求超时，是否重试？")
-                        .default(true)
-                        .interact()? {
+                    // 非交互模式下不重试，避免无限等待
+                    if prompt::is_non_interactive() || !prompt::confirm("请求超时，是否重试？", true)? {
                         return Err(anyhow::anyhow!("请求超时"));
                     }
                     continue;
@@ -751,16 +772,33 @@ pub async fn create_translator(config: &Config) -> anyhow::Result<Box<dyn Transl
     create_translator_for_service(service_config).await
 }
 
-pub async fn translate_with_fallback(config: &Config, text: &str, direction: &crate::config::TranslateDirection) -> anyhow::Result<String> {
-    let mut tried_services = Vec::new();
-
+pub async fn translate_with_fallback(config: &Config, text: &str, direction: &LanguagePair, kind: TextKind) -> anyhow::Result<String> {
     // 如果已设置环境变量，直接返回原文
     if std::env::var("GIT_COMMIT_HELPER_NO_TRANSLATE").is_ok() {
         return Ok(text.trim().to_string());
     }
 
+    // 翻译记忆只保存提交信息的译文
+    if kind == TextKind::Document {
+        return translate_with_services(config, text, direction, kind).await;
+    }
+
+    // 所有片段都有翻译记忆时不再请求 AI
+    if let Some(result) = tm::recall(text, direction) {
+        debug!("翻译记忆命中全部片段，跳过 AI 请求");
+        return Ok(result);
+    }
+
+    let result = translate_with_services(config, text, direction, kind).await?;
+    tm::remember(text, &result, direction);
+    Ok(result)
+}
+
+async fn translate_with_services(config: &Config, text: &str, direction: &LanguagePair, kind: TextKind) -> anyhow::Result<String> {
+    let mut tried_services = Vec::new();
+
     debug!("尝试使用默认服务 {:?}", config.default_service);
-    if let Some(result) = try_translate(&config.default_service, config, text, direction).await {
+    if let Some(result) = try_translate(&config.default_service, config, text, direction, kind).await {
         return result;
     }
     tried_services.push(config.default_service.clone());
@@ -771,7 +809,7 @@ pub async fn translate_with_fallback(config: &Config, text: &str, direction: &cr
         }
 
         debug!("尝试使用备选服务 {:?}", service_config.service);
-        if let Some(result) = try_translate(&service_config.service, config, text, direction).await {
+        if let Some(result) = try_translate(&service_config.service, config, text, direction, kind).await {
             return result;
         }
         tried_services.push(service_config.service.clone());
@@ -779,7 +817,7 @@ pub async fn translate_with_fallback(config: &Config, text: &str, direction: &cr
 
     while let Some(service) = select_retry_service(config, &tried_services)? {
         debug!("用户选择使用 {:?} 重试", service);
-        if let Some(result) = try_translate(&service, config, text, direction).await {
+        if let Some(result) = try_translate(&service, config, text, direction, kind).await {
             return result;
         }
         tried_services.push(service);
@@ -788,12 +826,12 @@ pub async fn translate_with_fallback(config: &Config, text: &str, direction: &cr
     Err(anyhow::anyhow!("所有AI服务均失败"))
 }
 
-async fn try_translate(service: &AIService, config: &Config, text: &str, direction: &crate::config::TranslateDirection) -> Option<anyhow::Result<String>> {
+async fn try_translate(service: &AIService, config: &Config, text: &str, direction: &LanguagePair, kind: TextKind) -> Option<anyhow::Result<String>> {
     let service_config = config.services.iter()
         .find(|s| s.service == *service)?;
 
     let translator = create_translator_for_service(service_config).await.ok()?;
-    match translator.translate(text, direction).await {
+    match translator.translate_as(text, direction, kind).await {
         Ok(result) => Some(Ok(result)),
         Err(e) => {
             warn!("{:?} 服务翻译失败: {}", service, e);
@@ -815,18 +853,12 @@ fn select_retry_service(config: &Config, tried_services: &[AIService]) -> anyhow
         .map(|s| format!("{:?}", s.service))
         .collect();
 
-    println!("\n之前的翻译尝试都失败了，是否要使用其他服务重试？");
-    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .default(true)
-        .interact()? {
+    eprintln!();
+    if !prompt::confirm("之前的翻译尝试都失败了，是否要使用其他服务重试？", true)? {
         return Ok(None);
     }
 
-    let selection = Select::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt("请选择要使用的服务")
-        .items(&options)
-        .default(0)
-        .interact()?;
+    let selection = prompt::select("请选择要使用的服务", &options, 0)?;
 
     Ok(Some(available_services[selection].service.clone()))
 }
@@ -0,0 +1,136 @@
+// 批量翻译模块
+// 将多段文本用分隔行合并为一次翻译请求，按条目数和字符数分批；
+// 结果无法按分隔行拆分回各条目时改为逐条翻译
+
+use crate::ai_service::{self, TextKind};
+use crate::config::Config;
+use crate::language::LanguagePair;
+use log::warn;
+use regex::Regex;
+
+/// 每批最多翻译的条目数
+const BATCH_SIZE: usize = 10;
+
+/// 每批原文的最大字符数
+const MAX_BATCH_CHARS: usize = 4000;
+
+/// 批次中各条目之间的分隔行
+fn marker(n: usize) -> String {
+    format!("@@@ {} @@@", n)
+}
+
+/// 将多条文本合并为一次翻译请求的内容
+fn join_batch(texts: &[&str]) -> String {
+    texts.iter()
+        .enumerate()
+        .map(|(i, text)| format!("{}\n{}", marker(i + 1), text))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// 按分隔行拆分批量翻译的结果，条目数量或顺序不一致时返回 None
+fn split_batch(translated: &str, count: usize) -> Option<Vec<String>> {
+    let regex = Regex::new(r"(?m)^\s*@@@ (\d+) @@@\s*$").unwrap();
+    let markers: Vec<_> = regex.captures_iter(translated).collect();
+    let numbers: Vec<usize> = markers.iter().filter_map(|c| c[1].parse().ok()).collect();
+    if numbers != (1..=count).collect::<Vec<_>>() {
+        return None;
+    }
+    let mut texts = Vec::new();
+    for (i, captures) in markers.iter().enumerate() {
+        let start = captures.get(0).unwrap().end();
+        let end = markers.get(i + 1).map(|c| c.get(0).unwrap().start()).unwrap_or(translated.len());
+        texts.push(translated[start..end].trim().to_string());
+    }
+    Some(texts)
+}
+
+/// 按条目数和字符数分批，返回每批的条目数
+fn chunk_sizes(texts: &[&str]) -> Vec<usize> {
+    let mut sizes = Vec::new();
+    let mut count = 0;
+    let mut chars = 0;
+    for text in texts {
+        let len = text.chars().count();
+        if count > 0 && (count >= BATCH_SIZE || chars + len > MAX_BATCH_CHARS) {
+            sizes.push(count);
+            count = 0;
+            chars = 0;
+        }
+        count += 1;
+        chars += len;
+    }
+    if count > 0 {
+        sizes.push(count);
+    }
+    sizes
+}
+
+/// 翻译一批条目：合并为一次请求，结果无法拆分时逐条翻译
+async fn translate_batch(config: &Config, texts: &[&str], direction: &LanguagePair, kind: TextKind) -> anyhow::Result<Vec<String>> {
+    if texts.len() > 1 {
+        let translated = ai_service::translate_with_fallback(config, &join_batch(texts), direction, kind).await?;
+        match split_batch(&translated, texts.len()) {
+            Some(results) => return Ok(results),
+            None => warn!("批量翻译的结果无法按条目拆分，改为逐条翻译"),
+        }
+    }
+
+    let mut results = Vec::new();
+    for text in texts {
+        results.push(ai_service::translate_with_fallback(config, text, direction, kind).await?);
+    }
+    Ok(results)
+}
+
+/// 分批翻译多条文本，相同的文本只翻译一次，返回与输入顺序一致的译文
+pub async fn translate_all(config: &Config, texts: &[&str], direction: &LanguagePair, kind: TextKind) -> anyhow::Result<Vec<String>> {
+    let mut unique: Vec<&str> = Vec::new();
+    for text in texts {
+        if !unique.contains(text) {
+            unique.push(text);
+        }
+    }
+
+    let sizes = chunk_sizes(&unique);
+    let mut translated = Vec::new();
+    let mut start = 0;
+    for (i, size) in sizes.iter().enumerate() {
+        eprintln!("正在翻译第 {}/{} 批（{} 条）...", i + 1, sizes.len(), size);
+        translated.extend(translate_batch(config, &unique[start..start + size], direction, kind).await?);
+        eprintln!();
+        start += size;
+    }
+
+    Ok(texts.iter()
+        .map(|text| {
+            let index = unique.iter().position(|u| u == text).unwrap();
+            translated[index].clone()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_join_and_split() {
+        let joined = join_batch(&["fix: 修复崩溃", "1. 提前返回\n2. 补充测试"]);
+        assert_eq!(joined, "@@@ 1 @@@\nfix: 修复崩溃\n\n@@@ 2 @@@\n1. 提前返回\n2. 补充测试");
+
+        let translated = "@@@ 1 @@@\nfix: fix crash\n\n@@@ 2 @@@\n1. Return early\n2. Add tests\n";
+        assert_eq!(split_batch(translated, 2).unwrap(), vec!["fix: fix crash", "1. Return early\n2. Add tests"]);
+        assert!(split_batch("fix: fix crash\n\n1. Return early", 2).is_none());
+        assert!(split_batch("@@@ 2 @@@\na\n@@@ 1 @@@\nb", 2).is_none());
+    }
+
+    #[test]
+    fn test_chunk_sizes() {
+        let long = "长".repeat(MAX_BATCH_CHARS);
+        let mut texts = vec!["修复"; 12];
+        texts.push(&long);
+        texts.push("修复");
+        assert_eq!(chunk_sizes(&texts), vec![10, 2, 1, 1]);
+    }
+}
@@ -0,0 +1,79 @@
+// 本地缓存模块
+// 将可复用的计算结果以 JSON 形式保存在用户缓存目录中，按命名空间和键区分
+
+use directories::ProjectDirs;
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    key: String,
+    created_at: u64,
+    value: T,
+}
+
+pub fn cache_dir() -> Option<PathBuf> {
+    ProjectDirs::from("com", "githelper", "git-commit-helper")
+        .map(|dirs| dirs.cache_dir().to_path_buf())
+}
+
+/// 缓存文件路径：文件名为键的 SHA-1，不随 Rust 版本变化
+fn entry_path(namespace: &str, key: &str) -> Option<PathBuf> {
+    let digest = Sha1::digest(key.as_bytes());
+    let name: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    cache_dir().map(|dir| dir.join(namespace).join(format!("{}.json", name)))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 读取缓存，不存在、已过期或内容无效时返回 None
+pub fn load<T: DeserializeOwned>(namespace: &str, key: &str, ttl: Duration) -> Option<T> {
+    let path = entry_path(namespace, key)?;
+    let content = fs::read_to_string(&path).ok()?;
+    let entry: Entry<T> = serde_json::from_str(&content).ok()?;
+    if entry.key != key || now().saturating_sub(entry.created_at) > ttl.as_secs() {
+        debug!("缓存已失效: {}", path.display());
+        return None;
+    }
+    debug!("命中缓存: {}", path.display());
+    Some(entry.value)
+}
+
+/// 写入缓存
+pub fn store<T: Serialize>(namespace: &str, key: &str, value: &T) -> anyhow::Result<()> {
+    let path = entry_path(namespace, key)
+        .ok_or_else(|| anyhow::anyhow!("无法确定缓存目录"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let entry = Entry {
+        key: key.to_string(),
+        created_at: now(),
+        value,
+    };
+    fs::write(&path, serde_json::to_string(&entry)?)?;
+    debug!("已写入缓存: {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_path_is_stable() {
+        let Some(path) = entry_path("history", "abc") else { return };
+        assert_eq!(path.file_name().unwrap(), "a9993e364706816aba3e25717850c26c9cd0d89d.json");
+        assert!(path.parent().unwrap().ends_with("history"));
+    }
+}
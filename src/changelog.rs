@@ -0,0 +1,416 @@
+// 变更日志模块
+// 从提交历史中提取面向用户的 Log 字段，按提交类型分组，
+// 生成 Markdown、Debian changelog 或 RPM %changelog 格式的发布说明
+
+use crate::ai_service;
+use crate::commit::{CommitMessage, DEFAULT_BILINGUAL_TEMPLATE};
+use crate::config;
+use crate::detection;
+use crate::language;
+use crate::output;
+use log::debug;
+use regex::Regex;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// 变更日志格式
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ChangelogFormat {
+    /// Markdown 发布说明
+    Markdown,
+    /// debian/changelog 条目
+    Debian,
+    /// RPM spec 的 %changelog 条目
+    Rpm,
+}
+
+/// 变更日志使用的语言：保留全部语言，或只保留指定的 BCP-47 语言
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangelogLanguage {
+    All,
+    Only(Vec<String>),
+}
+
+impl ChangelogLanguage {
+    /// 解析 --lang 参数：all 或逗号分隔的 BCP-47 代码，如 ja 或 en,zh
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        if value.trim().eq_ignore_ascii_case("all") {
+            return Ok(ChangelogLanguage::All);
+        }
+        let mut languages: Vec<String> = Vec::new();
+        for code in value.split(',').filter(|c| !c.trim().is_empty()) {
+            let code = language::normalize(code)?;
+            if !languages.contains(&code) {
+                languages.push(code);
+            }
+        }
+        if languages.is_empty() {
+            return Err(anyhow::anyhow!("至少需要指定一种语言"));
+        }
+        Ok(ChangelogLanguage::Only(languages))
+    }
+
+    fn includes(&self, language: &str) -> bool {
+        match self {
+            ChangelogLanguage::All => true,
+            ChangelogLanguage::Only(languages) => languages.iter().any(|l| language::same_language(l, language)),
+        }
+    }
+
+    /// 分组标题：中文使用中文标题，其他语言使用英文标题，按语言顺序排列
+    fn label(&self, (_, zh, en): (&str, &str, &str)) -> String {
+        let ChangelogLanguage::Only(languages) = self else {
+            return format!("{} / {}", zh, en);
+        };
+        let mut labels: Vec<&str> = Vec::new();
+        for code in languages {
+            let label = if language::same_language(code, "zh") { zh } else { en };
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        labels.join(" / ")
+    }
+}
+
+pub struct ChangelogOptions {
+    pub range: String,
+    pub format: ChangelogFormat,
+    pub language: ChangelogLanguage,
+    pub include_all: bool,  // 没有 Log 字段的提交使用标题
+    pub summarize: bool,  // 使用 AI 精简合并各分组的条目
+    pub version: Option<String>,
+    pub output: Option<PathBuf>,
+    pub prepend: bool,  // 写入文件时插入到原有内容之前
+}
+
+/// 一个提交中提取出的变更内容
+#[derive(Debug, PartialEq)]
+struct Entry {
+    sha: String,
+    commit_type: Option<String>,
+    items: Vec<String>,
+}
+
+/// 分组中的一个条目，sha 为来源提交，AI 精简合并后的条目没有对应的提交
+#[derive(Debug, PartialEq)]
+struct Item {
+    text: String,
+    sha: Option<String>,
+}
+
+/// 分组：提交类型、中文标题、英文标题
+const GROUPS: &[(&str, &str, &str)] = &[
+    ("feat", "新功能", "Features"),
+    ("fix", "问题修复", "Bug Fixes"),
+    ("perf", "性能优化", "Performance"),
+];
+const OTHER_GROUP: (&str, &str, &str) = ("", "其他改动", "Other Changes");
+
+const SUMMARIZE_PROMPT: &str = r#"You are writing user-facing release notes. Merge duplicated or closely related items in the following list, keep the original language of each item, and make them concise. Respond with ONLY the items, one per line, each starting with "- ". DO NOT add headings or any other text."#;
+
+/// 从提交信息中提取变更条目
+fn parse_entry(sha: &str, raw: &str, template: &str, language: &ChangelogLanguage, include_all: bool) -> Option<Entry> {
+    let message = CommitMessage::parse_with(raw, template);
+    let parts = output::parse_parts(raw, template);
+
+    // Log 字段也可能与 Fixes 等字段一起出现在 trailer 中
+    let trailer_logs = message.trailers.iter()
+        .filter(|t| t.is("Log"))
+        .map(|t| (detection::detect(&t.value).unwrap_or("en"), t.value.as_str()));
+    let logs: Vec<&str> = parts.parts.iter()
+        .filter_map(|p| Some((p.language.as_str(), p.log.as_deref()?)))
+        .chain(trailer_logs)
+        .filter(|(lang, _)| language.includes(lang))
+        .map(|(_, log)| log)
+        .collect();
+    let items = if !logs.is_empty() {
+        vec![logs.join(" / ")]
+    } else if include_all {
+        // 去掉标题中的类型前缀
+        let title = Regex::new(r"^[a-z]+(?:\([^)]*\))?!?:\s*").unwrap()
+            .replace(&message.title, "")
+            .to_string();
+        vec![title]
+    } else {
+        return None;
+    };
+
+    Some(Entry {
+        sha: sha.to_string(),
+        commit_type: parts.commit_type,
+        items,
+    })
+}
+
+/// 按提交类型分组，返回（标题，条目）列表，保持提交顺序
+fn group_entries(entries: &[Entry], language: &ChangelogLanguage) -> Vec<(String, Vec<Item>)> {
+    let mut groups: Vec<(String, Vec<Item>)> = Vec::new();
+    for group in GROUPS.iter().copied().chain(std::iter::once(OTHER_GROUP)) {
+        let items: Vec<Item> = entries.iter()
+            .filter(|e| {
+                let commit_type = e.commit_type.as_deref().unwrap_or_default();
+                if group.0.is_empty() {
+                    !GROUPS.iter().any(|(t, _, _)| *t == commit_type)
+                } else {
+                    commit_type == group.0
+                }
+            })
+            .flat_map(|e| e.items.iter().map(|text| Item { text: text.clone(), sha: Some(e.sha.clone()) }))
+            .collect();
+        if !items.is_empty() {
+            groups.push((language.label(group), items));
+        }
+    }
+    groups
+}
+
+fn render_markdown(version: Option<&str>, range: &str, groups: &[(String, Vec<Item>)]) -> String {
+    let mut result = format!("## {}\n", version.unwrap_or(range));
+    for (label, items) in groups {
+        result.push_str(&format!("\n### {}\n\n", label));
+        for item in items {
+            match &item.sha {
+                Some(sha) => result.push_str(&format!("- {} ({})\n", item.text, sha)),
+                None => result.push_str(&format!("- {}\n", item.text)),
+            }
+        }
+    }
+    result
+}
+
+/// 包信息，用于 Debian 和 RPM 格式
+struct PackageInfo {
+    name: String,
+    version: String,
+    distribution: String,
+    maintainer: String,
+    date: String,
+}
+
+fn render_debian(info: &PackageInfo, groups: &[(String, Vec<Item>)]) -> String {
+    let mut result = format!("{} ({}) {}; urgency=medium\n\n", info.name, info.version, info.distribution);
+    for (_, items) in groups {
+        for item in items {
+            result.push_str(&format!("  * {}\n", item.text));
+        }
+    }
+    result.push_str(&format!("\n -- {}  {}\n", info.maintainer, info.date));
+    result
+}
+
+fn render_rpm(info: &PackageInfo, groups: &[(String, Vec<Item>)]) -> String {
+    let mut result = format!("* {} {} - {}-1\n", info.date, info.maintainer, info.version);
+    for (_, items) in groups {
+        for item in items {
+            result.push_str(&format!("- {}\n", item.text));
+        }
+    }
+    result
+}
+
+fn git(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git").env("LC_ALL", "C").args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "执行 git {} 失败: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 范围的终点，用于确定版本号和日期
+fn range_head(range: &str) -> &str {
+    match range.rsplit_once("..") {
+        Some((_, head)) if !head.trim_start_matches('.').is_empty() => head.trim_start_matches('.'),
+        Some(_) => "HEAD",
+        None => range,
+    }
+}
+
+fn package_info(options: &ChangelogOptions) -> anyhow::Result<PackageInfo> {
+    let head = range_head(&options.range);
+    let version = match &options.version {
+        Some(version) => version.clone(),
+        None => git(&["describe", "--tags", "--abbrev=0", head])
+            .map(|tag| tag.trim_start_matches('v').to_string())
+            .map_err(|_| anyhow::anyhow!("无法从标签确定版本号，请使用 --release 指定"))?,
+    };
+
+    // 包名和发行版优先取自 debian/changelog 的第一条记录
+    let first_entry = std::fs::read_to_string("debian/changelog").ok()
+        .and_then(|content| content.lines().next().map(|l| l.to_string()))
+        .and_then(|line| {
+            let captures = Regex::new(r"^(\S+) \([^)]*\) ([^;]+);").unwrap().captures(&line)?;
+            Some((captures[1].to_string(), captures[2].trim().to_string()))
+        });
+    let (name, distribution) = match first_entry {
+        Some(entry) => entry,
+        None => {
+            let toplevel = git(&["rev-parse", "--show-toplevel"])?;
+            let name = std::path::Path::new(&toplevel)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            (name, "unstable".to_string())
+        }
+    };
+
+    // 维护者优先使用 Debian 打包工具的环境变量
+    let maintainer = match (std::env::var("DEBFULLNAME"), std::env::var("DEBEMAIL")) {
+        (Ok(name), Ok(email)) => format!("{} <{}>", name, email),
+        _ => {
+            let ident = git(&["var", "GIT_COMMITTER_IDENT"])?;
+            ident.rsplit_once('>').map(|(who, _)| format!("{}>", who)).unwrap_or(ident)
+        }
+    };
+
+    // 使用范围终点提交的时间，保证多次生成的结果一致
+    let date = match options.format {
+        ChangelogFormat::Rpm => git(&["log", "-1", "--date=format:%a %b %d %Y", "--format=%cd", head])?,
+        _ => git(&["log", "-1", "--format=%cD", head])?,
+    };
+
+    Ok(PackageInfo { name, version, distribution, maintainer, date })
+}
+
+/// 使用 AI 精简各分组的条目
+async fn summarize_groups(groups: &mut [(String, Vec<Item>)]) -> anyhow::Result<()> {
+    let config = config::Config::load()?;
+    let translator = ai_service::create_translator(&config).await?;
+
+    for (label, items) in groups.iter_mut() {
+        if items.len() < 2 {
+            continue;
+        }
+        eprintln!("正在精简「{}」分组的 {} 个条目...", label, items.len());
+        let list = items.iter()
+            .map(|item| format!("- {}", item.text))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let response = translator.chat(SUMMARIZE_PROMPT, &list).await?;
+        eprintln!();
+        let summarized: Vec<Item> = response.lines()
+            .filter_map(|line| line.trim().strip_prefix("- "))
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| Item { text: line.to_string(), sha: None })
+            .collect();
+        if summarized.is_empty() {
+            eprintln!("警告: AI 未返回有效的条目，保留原始内容");
+            continue;
+        }
+        *items = summarized;
+    }
+    Ok(())
+}
+
+/// 生成变更日志并输出
+pub async fn run(options: ChangelogOptions) -> anyhow::Result<()> {
+    let template = config::Config::load()
+        .map(|config| config.bilingual_template)
+        .unwrap_or_else(|_| DEFAULT_BILINGUAL_TEMPLATE.to_string());
+    let log = git(&["log", "--no-merges", "--format=%h%x1f%B%x00", &options.range])?;
+    let entries: Vec<Entry> = log.split('\0')
+        .filter_map(|record| record.trim().split_once('\x1f'))
+        .filter_map(|(sha, raw)| parse_entry(sha, raw, &template, &options.language, options.include_all))
+        .collect();
+    debug!("提取到 {} 个包含变更说明的提交", entries.len());
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!("范围 {} 中没有包含 Log 字段的提交，可使用 --all 改用提交标题", options.range));
+    }
+
+    let mut groups = group_entries(&entries, &options.language);
+    if options.summarize {
+        summarize_groups(&mut groups).await?;
+    }
+
+    let content = match options.format {
+        ChangelogFormat::Markdown => render_markdown(options.version.as_deref(), &options.range, &groups),
+        ChangelogFormat::Debian => render_debian(&package_info(&options)?, &groups),
+        ChangelogFormat::Rpm => render_rpm(&package_info(&options)?, &groups),
+    };
+
+    match &options.output {
+        Some(path) => {
+            let existing = if options.prepend {
+                std::fs::read_to_string(path).unwrap_or_default()
+            } else {
+                String::new()
+            };
+            let separator = if existing.is_empty() { "" } else { "\n" };
+            std::fs::write(path, format!("{}{}{}", content, separator, existing))?;
+            eprintln!("变更日志已写入: {}", path.display());
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<Entry> {
+        let raw = [
+            ("a1", "feat(auth): add login\n\n1. Add login\n\nLog: Added login\n\nfeat(auth): 添加登录\n\n1. 添加登录\n\nLog: 新增登录功能\n\nChange-Id: I1"),
+            ("b2", "fix: crash on empty input\n\nLog: Fixed a crash (empty input)"),
+            ("c3", "chore: bump deps"),
+            ("d4", "docs: update readme\n\nFixes: #3\nLog: Documented the login feature"),
+        ];
+        raw.iter()
+            .filter_map(|(sha, message)| parse_entry(sha, message, DEFAULT_BILINGUAL_TEMPLATE, &ChangelogLanguage::All, false))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_entry_logs() {
+        let entries = entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].items, vec!["Added login / 新增登录功能"]);
+
+        let zh = ChangelogLanguage::parse("zh").unwrap();
+        let entry = parse_entry("a1", "feat: x\n\nLog: English\n\nfeat: 中文\n\nLog: 中文说明", DEFAULT_BILINGUAL_TEMPLATE, &zh, false).unwrap();
+        assert_eq!(entry.items, vec!["中文说明"]);
+
+        let ja = ChangelogLanguage::parse("en,ja").unwrap();
+        assert_eq!(ja, ChangelogLanguage::Only(vec!["en".to_string(), "ja".to_string()]));
+        let entry = parse_entry("e5", "feat: x\n\nLog: English\n\nfeat: ログイン\n\nLog: ログイン機能を追加", DEFAULT_BILINGUAL_TEMPLATE, &ja, false).unwrap();
+        assert_eq!(entry.items, vec!["English / ログイン機能を追加"]);
+        assert_eq!(ChangelogLanguage::parse("all").unwrap(), ChangelogLanguage::All);
+        assert!(ChangelogLanguage::parse("中文").is_err());
+
+        let entry = parse_entry("c3", "chore(deps): bump deps", DEFAULT_BILINGUAL_TEMPLATE, &ChangelogLanguage::All, true).unwrap();
+        assert_eq!(entry.items, vec!["bump deps"]);
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let groups = group_entries(&entries(), &ChangelogLanguage::parse("en").unwrap());
+        assert_eq!(groups.iter().map(|(label, _)| label.as_str()).collect::<Vec<_>>(), vec!["Features", "Bug Fixes", "Other Changes"]);
+        let labels = group_entries(&entries(), &ChangelogLanguage::parse("ja,zh").unwrap());
+        assert_eq!(labels[0].0, "Features / 新功能");
+
+        let markdown = render_markdown(Some("1.0.0"), "v0.9..v1.0", &groups);
+        assert!(markdown.starts_with("## 1.0.0\n\n### Features\n\n- Added login / 新增登录功能 (a1)\n"));
+        assert!(markdown.contains("\n- Fixed a crash (empty input) (b2)\n"));
+
+        let info = PackageInfo {
+            name: "pkg".to_string(),
+            version: "1.0.0".to_string(),
+            distribution: "unstable".to_string(),
+            maintainer: "A <a@b.c>".to_string(),
+            date: "Tue, 25 Feb 2026 08:19:32 +0800".to_string(),
+        };
+        let debian = render_debian(&info, &groups);
+        assert!(debian.starts_with("pkg (1.0.0) unstable; urgency=medium\n\n  * Added login / 新增登录功能\n"));
+        assert!(debian.contains("\n  * Fixed a crash (empty input)\n"));
+        assert!(debian.ends_with("\n -- A <a@b.c>  Tue, 25 Feb 2026 08:19:32 +0800\n"));
+
+        let info = PackageInfo { date: "Tue Feb 25 2026".to_string(), ..info };
+        let rpm = render_rpm(&info, &groups);
+        assert!(rpm.starts_with("* Tue Feb 25 2026 A <a@b.c> - 1.0.0-1\n- Added login / 新增登录功能\n- Fixed a crash (empty input)\n"));
+    }
+}
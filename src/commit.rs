@@ -1,7 +1,11 @@
 use regex::Regex;
 use crate::ai_service;
 use crate::config;
+use crate::detection;
 use crate::git;
+use crate::issue::{self, IssueDetails, IssueRef};
+use crate::language::{self, LanguagePair};
+use crate::trailer::{self, Trailer};
 
 /// 从提交消息中提取 Change-Id
 fn extract_change_id(message: &str) -> Option<String> {
@@ -12,165 +16,74 @@ fn extract_change_id(message: &str) -> Option<String> {
 }
 
 /// 将 Change-Id 添加到提交消息中（如果还没有的话）
+/// Change-Id 必须位于最后一段的末尾（在 Signed-off-by 等字段之后）
 fn append_change_id(message: &str, change_id: &str) -> String {
     // 检查消息中是否已经有 Change-Id
     if message.contains("Change-Id:") {
         return message.to_string();
     }
-    
-    let mut result = message.to_string();
-    
-    // 确保消息末尾有换行
-    if !result.ends_with('\n') {
-        result.push('\n');
-    }
-    
-    // 添加 Change-Id（如果有其他标记，Change-Id 应该在最后）
-    result.push('\n');
-    result.push_str(&format!("Change-Id: {}", change_id));
-    
-    result
+    trailer::apply(message, [Trailer::new("Change-Id", change_id)])
 }
 
-// 语言模式枚举
-#[derive(Debug, Clone, Copy)]
+/// amend 时需要从原提交中保留的 trailer：排除 Change-Id（由 append_change_id 单独处理）
+/// 以及新内容中已出现的同名字段
+fn preserved_trailers(original: &CommitMessage, content: &str) -> Vec<Trailer> {
+    original.trailers.iter()
+        .filter(|t| {
+            !t.is("Change-Id") && !content.lines().any(|line| {
+                line.trim().split(':').next()
+                    .is_some_and(|k| t.is(k.trim()))
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+// 语言模式：单语言，或双语（第一种语言在前，第二种语言为其翻译），语言使用 BCP-47 代码
+#[derive(Debug, Clone, PartialEq)]
 enum LanguageMode {
-    ChineseOnly,
-    EnglishOnly,
-    Bilingual,
+    Single(String),
+    Bilingual(String, String),
 }
 
 /// 解析 issues 参数并生成相应的引用字段
+#[cfg(test)]
 fn parse_issue_reference(issues: &str) -> anyhow::Result<String> {
-    let mut fixes_refs = Vec::new();
-    let mut pms_refs = Vec::new();
-
-    // 按空格和逗号分割多个链接
-    let links: Vec<&str> = issues.split_whitespace()
-        .flat_map(|s| s.split(','))
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    for link in links {
-        let link = link.trim();
-
-        // 处理 GitHub issue URL
-        if link.starts_with("https://github.com/") {
-            match parse_github_issue(link) {
-                Ok(ref_str) => {
-                    // 提取 Fixes: 后面的部分
-                    if let Some(fix_ref) = ref_str.strip_prefix("Fixes: ") {
-                        fixes_refs.push(fix_ref.to_string());
-                    }
-                }
-                Err(e) => return Err(e),
-            }
-        }
-        // 处理 PMS 链接
-        else if link.contains("pms.uniontech.com") {
-            match parse_pms_link(link) {
-                Ok(ref_str) => {
-                    // 提取 PMS: 后面的部分
-                    if let Some(pms_ref) = ref_str.strip_prefix("PMS: ") {
-                        pms_refs.push(pms_ref.to_string());
-                    }
-                }
-                Err(e) => return Err(e),
-            }
-        }
-        // 处理简单的 issue 数字（假设是当前项目的 GitHub issue）
-        else if let Ok(_issue_num) = link.parse::<u32>() {
-            fixes_refs.push(format!("#{}", link));
-        }
-        else {
-            return Err(anyhow::anyhow!("无法解析 issue 引用格式: {}", link));
-        }
-    }
-
-    // 组合结果
-    let mut result = Vec::new();
-
-    if !fixes_refs.is_empty() {
-        result.push(format!("Fixes: {}", fixes_refs.join(" ")));
-    }
-
-    if !pms_refs.is_empty() {
-        result.push(format!("PMS: {}", pms_refs.join(" ")));
-    }
-
-    if result.is_empty() {
-        return Err(anyhow::anyhow!("没有找到有效的 issue 引用"));
-    }
-
-    Ok(result.join("\n"))
+    Ok(issue::format_refs(&issue::IssueRegistry::new(&[], &config::JiraConfig::default()).parse_all(issues)?))
 }
 
-/// 解析 GitHub issue URL 并生成 Fixes 字段
-fn parse_github_issue(url: &str) -> anyhow::Result<String> {
-    let issue_regex = Regex::new(r"https://github\.com/([^/]+/[^/]+)/issues/(\d+)")?;
-
-    if let Some(captures) = issue_regex.captures(url) {
-        let repo = captures.get(1).unwrap().as_str();
-        let issue_num = captures.get(2).unwrap().as_str();
-
-        // 检查是否是当前项目
-        if is_current_project_repo(repo)? {
-            Ok(format!("Fixes: #{}", issue_num))
-        } else {
-            Ok(format!("Fixes: {}#{}", repo, issue_num))
+/// 汇总命令行指定的 issue 引用和从分支名中识别的引用
+fn collect_issue_refs(issues: Option<&str>, config: &config::Config, amend: bool) -> anyhow::Result<Vec<IssueRef>> {
+    let mut refs = Vec::new();
+    if let Some(issues_str) = issues {
+        match issue::IssueRegistry::new(&config.issue_trackers, &config.jira).parse_all(issues_str) {
+            Ok(parsed) => refs = parsed,
+            Err(e) => eprintln!("警告: 解析 issues 参数失败: {}", e),
         }
-    } else {
-        Err(anyhow::anyhow!("无效的 GitHub issue URL 格式: {}", url))
     }
-}
 
-/// 解析 PMS 链接并生成 PMS 字段
-fn parse_pms_link(url: &str) -> anyhow::Result<String> {
-    // 匹配 bug-view、task-view、story-view 格式
-    let bug_regex = Regex::new(r"bug-view-(\d+)\.html")?;
-    let task_regex = Regex::new(r"task-view-(\d+)\.html")?;
-    let story_regex = Regex::new(r"story-view-(\d+)\.html")?;
-
-    if let Some(captures) = bug_regex.captures(url) {
-        let id = &captures[1];
-        Ok(format!("PMS: BUG-{}", id))
-    } else if let Some(captures) = task_regex.captures(url) {
-        let id = &captures[1];
-        Ok(format!("PMS: TASK-{}", id))
-    } else if let Some(captures) = story_regex.captures(url) {
-        let id = &captures[1];
-        Ok(format!("PMS: STORY-{}", id))
-    } else {
-        Err(anyhow::anyhow!("无效的 PMS 链接格式: {}", url))
+    // amend 模式下沿用原提交中的引用，不再从分支名识别
+    if amend || !config.branch_issues.enabled {
+        return Ok(refs);
     }
-}
-
-/// 检查给定的仓库是否是当前项目的仓库
-fn is_current_project_repo(repo: &str) -> anyhow::Result<bool> {
-    use std::process::Command;
-
-    // 获取当前仓库的远程 URL
-    let output = Command::new("git")
-        .args(&["remote", "get-url", "origin"])
-        .output()?;
+    let Some(branch) = git::get_current_branch() else {
+        return Ok(refs);
+    };
 
-    if !output.status.success() {
-        return Ok(false);
+    let detected: Vec<IssueRef> = issue::refs_from_branch(&branch, &config.branch_issues.patterns)
+        .into_iter()
+        .filter(|r| !refs.contains(r))
+        .collect();
+    if detected.is_empty() {
+        return Ok(refs);
     }
 
-    let remote_url = String::from_utf8_lossy(&output.stdout);
-    let remote_url = remote_url.trim();
-
-    // 从远程 URL 中提取仓库名称
-    // 支持 HTTPS 和 SSH 格式
-    let repo_regex = Regex::new(r"github\.com[:/]([^/]+/[^/\.]+)")?;
-
-    if let Some(captures) = repo_regex.captures(remote_url) {
-        let current_repo = captures.get(1).unwrap().as_str();
-        Ok(current_repo == repo)
-    } else {
-        Ok(false)
+    eprintln!("从分支名 {} 中识别到 issue 引用:", branch);
+    eprintln!("{}", issue::format_refs(&detected));
+    if prompt::confirm("是否将这些引用添加到提交信息？", true)? {
+        refs.extend(detected);
     }
+    Ok(refs)
 }
 
 // 提示词模板常量
@@ -562,194 +475,444 @@ Please respond with ONLY the commit message following this format,
 DO NOT end commit titles with any punctuation."#;
 
 impl LanguageMode {
-    fn determine(only_chinese: bool, only_english: bool) -> Self {
-        if only_english {
-            Self::EnglishOnly
-        } else if only_chinese {
-            Self::ChineseOnly
-        } else {
-            Self::Bilingual
+    fn from_languages(languages: &[String]) -> Self {
+        match languages {
+            [single] => Self::Single(single.clone()),
+            [first, second, ..] => Self::Bilingual(first.clone(), second.clone()),
+            [] => Self::Bilingual("en".to_string(), "zh".to_string()),
         }
     }
 
-    fn template(&self, include_test_suggestions: bool, include_log: bool) -> &'static str {
-        match (self, include_test_suggestions, include_log) {
-            (Self::EnglishOnly, true, true) => ENGLISH_PROMPT_TEMPLATE,
-            (Self::EnglishOnly, false, true) => ENGLISH_PROMPT_TEMPLATE_NO_TEST,
-            (Self::EnglishOnly, true, false) => ENGLISH_PROMPT_TEMPLATE_NO_LOG,
-            (Self::EnglishOnly, false, false) => ENGLISH_PROMPT_TEMPLATE_NO_TEST_NO_LOG,
-            (Self::ChineseOnly, true, true) => CHINESE_PROMPT_TEMPLATE,
-            (Self::ChineseOnly, false, true) => CHINESE_PROMPT_TEMPLATE_NO_TEST,
-            (Self::ChineseOnly, true, false) => CHINESE_PROMPT_TEMPLATE_NO_LOG,
-            (Self::ChineseOnly, false, false) => CHINESE_PROMPT_TEMPLATE_NO_TEST_NO_LOG,
-            (Self::Bilingual, true, true) => BILINGUAL_PROMPT_TEMPLATE,
-            (Self::Bilingual, false, true) => BILINGUAL_PROMPT_TEMPLATE_NO_TEST,
-            (Self::Bilingual, true, false) => BILINGUAL_PROMPT_TEMPLATE_NO_LOG,
-            (Self::Bilingual, false, false) => BILINGUAL_PROMPT_TEMPLATE_NO_TEST_NO_LOG,
+    /// 是否使用中文提示词
+    fn is_chinese_only(&self) -> bool {
+        matches!(self, Self::Single(code) if language::is_simplified_chinese(code))
+    }
+
+    fn template(&self, include_test_suggestions: bool, include_log: bool) -> String {
+        let (english, chinese, bilingual) = match (include_test_suggestions, include_log) {
+            (true, true) => (ENGLISH_PROMPT_TEMPLATE, CHINESE_PROMPT_TEMPLATE, BILINGUAL_PROMPT_TEMPLATE),
+            (false, true) => (ENGLISH_PROMPT_TEMPLATE_NO_TEST, CHINESE_PROMPT_TEMPLATE_NO_TEST, BILINGUAL_PROMPT_TEMPLATE_NO_TEST),
+            (true, false) => (ENGLISH_PROMPT_TEMPLATE_NO_LOG, CHINESE_PROMPT_TEMPLATE_NO_LOG, BILINGUAL_PROMPT_TEMPLATE_NO_LOG),
+            (false, false) => (ENGLISH_PROMPT_TEMPLATE_NO_TEST_NO_LOG, CHINESE_PROMPT_TEMPLATE_NO_TEST_NO_LOG, BILINGUAL_PROMPT_TEMPLATE_NO_TEST_NO_LOG),
+        };
+        match self {
+            Self::Single(code) if language::same_language(code, "en") => english.to_string(),
+            Self::Single(code) if language::is_simplified_chinese(code) => chinese.to_string(),
+            Self::Single(code) => localize_template(english, code),
+            Self::Bilingual(first, second) if language::same_language(first, "en") && language::is_simplified_chinese(second) => {
+                bilingual.to_string()
+            }
+            Self::Bilingual(first, second) => localize_bilingual_template(bilingual, first, second),
         }
     }
 }
 
+/// 将英文模板改写为其他单一语言
+fn localize_template(template: &str, code: &str) -> String {
+    let name = language::english_name(code);
+    let mut prompt = template
+        .replace("in English only", &format!("in {} only", name))
+        .replace("Detailed explanation in English", &format!("Detailed explanation in {}", name))
+        .replace(
+            "DO NOT include any Chinese content",
+            &format!("Write the title, explanation, Log and Influence content in {}, but keep the type prefix and the \"Log:\"/\"Influence:\" field names in English", name),
+        );
+    prompt.push_str(&format!("\n\nThe example above only illustrates the format; write the commit message in {}.", name));
+    prompt
+}
+
+/// 将英文、中文双语模板改写为其他语言组合
+fn localize_bilingual_template(template: &str, first: &str, second: &str) -> String {
+    let (first, second) = (language::english_name(first), language::english_name(second));
+    let mut prompt = template
+        .replace("English", "\u{0}FIRST\u{0}")
+        .replace("Chinese", &second)
+        .replace("\u{0}FIRST\u{0}", &first);
+    prompt.push_str(&format!(
+        "\n\nThe example above only illustrates the layout; write the first part in {} and the second part in {}, keeping the type prefix and the \"Log:\"/\"Influence:\" field names in English.",
+        first, second
+    ));
+    prompt
+}
+
+/// 提示词中除模板以外的可选内容
+#[derive(Default)]
+struct PromptExtras<'a> {
+    user_message: Option<&'a str>,
+    original_message: Option<&'a str>,
+    squashed_messages: &'a [String],
+    style_examples: &'a [String],
+    context: Option<&'a str>,
+    issues: &'a [IssueDetails],
+    glossary: Option<&'a str>,
+}
+
 // 统一的提示词构建函数
-fn build_prompt(mode: LanguageMode, user_message: Option<&str>, include_test_suggestions: bool, include_log: bool, original_message: Option<&str>) -> String {
-    let mut prompt = String::from(mode.template(include_test_suggestions, include_log));
+fn build_prompt(mode: LanguageMode, include_test_suggestions: bool, include_log: bool, extras: &PromptExtras) -> String {
+    let mut prompt = mode.template(include_test_suggestions, include_log);
+
+    // 仓库历史中的提交信息，作为语气和 scope 命名的参考
+    let style_examples = extras.style_examples;
+    if !style_examples.is_empty() {
+        if mode.is_chinese_only() {
+            prompt.push_str("\n\n本仓库最近的提交信息示例（请参考其语气、scope 命名和格式习惯，但必须遵守上面要求的格式）：\n");
+        } else {
+            prompt.push_str("\n\nRecent commit messages in this repository (follow their tone, scope naming and conventions, but always keep the required format above):\n");
+        }
+        for example in style_examples {
+            prompt.push_str(&format!("---\n{}\n", example));
+        }
+        prompt.push_str("---\n");
+    }
 
     // 如果有原始提交信息（amend 模式），先添加它作为参考
-    if let Some(orig_msg) = original_message {
-        match mode {
-            LanguageMode::ChineseOnly => {
-                prompt.push_str(&format!("\n\n原始提交信息（请参考但不要完全照搬）：\n{}\n", orig_msg));
-            }
-            _ => {
-                prompt.push_str(&format!("\n\nOriginal commit message (for reference, but create improved version):\n{}\n", orig_msg));
-            }
+    if let Some(orig_msg) = extras.original_message {
+        if mode.is_chinese_only() {
+            prompt.push_str(&format!("\n\n原始提交信息（请参考但不要完全照搬）：\n{}\n", orig_msg));
+        } else {
+            prompt.push_str(&format!("\n\nOriginal commit message (for reference, but create improved version):\n{}\n", orig_msg));
         }
     }
 
-    if let Some(msg) = user_message {
-        match mode {
-            LanguageMode::ChineseOnly => {
-                prompt.push_str(&format!("\n\n用户描述：\n{}\n\n变更内容：\n", msg));
-            }
-            _ => {
-                prompt.push_str(&format!("\n\nUser Description:\n{}\n\nChanges:\n", msg));
+    // squash 模式下被合并的各个提交的信息
+    if !extras.squashed_messages.is_empty() {
+        if mode.is_chinese_only() {
+            prompt.push_str("\n\n以下提交将被合并为一个提交，请将它们整合为一条完整的提交信息，不要逐条罗列：\n");
+        } else {
+            prompt.push_str("\n\nThe following commits are squashed into one. Combine them into a single coherent commit message instead of listing them one by one:\n");
+        }
+        for message in extras.squashed_messages {
+            prompt.push_str(&format!("---\n{}\n", message));
+        }
+        prompt.push_str("---\n");
+    }
+
+    // 分支名、文件状态等仓库上下文
+    if let Some(context) = extras.context {
+        if mode.is_chinese_only() {
+            prompt.push_str(&format!("\n\n仓库上下文（分支名中可能包含任务或缺陷编号）：\n{}\n", context));
+        } else {
+            prompt.push_str(&format!("\n\nRepository context (the branch name may contain a ticket ID):\n{}\n", context));
+        }
+    }
+
+    // 关联 issue 的标题和描述，用于说明改动的原因
+    if !extras.issues.is_empty() {
+        if mode.is_chinese_only() {
+            prompt.push_str("\n\n关联的 issue（请结合其内容说明改动的原因）：\n");
+        } else {
+            prompt.push_str("\n\nLinked issues (use them to explain why the change was made):\n");
+        }
+        for details in extras.issues {
+            prompt.push_str(&format!("### {} {}\n", details.reference, details.title));
+            if !details.body.is_empty() {
+                prompt.push_str(&format!("{}\n", details.body));
             }
         }
+    }
+
+    // 术语表中固定的术语写法
+    if let Some(glossary) = extras.glossary {
+        if mode.is_chinese_only() {
+            prompt.push_str(&format!("\n\n术语表（涉及以下术语时必须使用对应的写法）：\n{}\n", glossary));
+        } else {
+            prompt.push_str(&format!("\n\nGlossary (when these terms are mentioned, always use exactly the listed wording in each language):\n{}\n", glossary));
+        }
+    }
+
+    if let Some(msg) = extras.user_message {
+        if mode.is_chinese_only() {
+            prompt.push_str(&format!("\n\n用户描述：\n{}\n\n变更内容：\n", msg));
+        } else {
+            prompt.push_str(&format!("\n\nUser Description:\n{}\n\nChanges:\n", msg));
+        }
     } else {
-        match mode {
-            LanguageMode::ChineseOnly => {
-                prompt.push_str("\n\n变更内容：\n");
-            }
-            _ => {
-                prompt.push_str("\n\nHere are the changes:\n");
-            }
+        if mode.is_chinese_only() {
+            prompt.push_str("\n\n变更内容：\n");
+        } else {
+            prompt.push_str("\n\nHere are the changes:\n");
         }
     }
 
     prompt
 }
 
+/// 默认的双语排列：默认语言的标题和正文在前，另一种语言的标题和正文在后
+pub const DEFAULT_BILINGUAL_TEMPLATE: &str = "{title}\n\n{body}\n\n{other_title}\n\n{other_body}";
+
+/// 预置的双语排列模板
+const BILINGUAL_TEMPLATE_PRESETS: &[(&str, &str)] = &[
+    ("stacked", DEFAULT_BILINGUAL_TEMPLATE),
+    ("other-first", "{other_title}\n\n{other_body}\n\n{title}\n\n{body}"),
+    ("separator", "{title}\n\n{body}\n\n---\n\n{other_title}\n\n{other_body}"),
+    ("trailer", "{title}\n\n{body}\n\n{other_body}\n\nTranslated-Title: {other_title}"),
+];
+
+/// 双语排列模板必须包含的占位符：原提交信息可能在任意一侧，缺少任何一个都会丢失原文
+const BILINGUAL_TEMPLATE_PLACEHOLDERS: &[&str] = &["{title}", "{body}", "{other_title}", "{other_body}"];
+
+/// 解析双语排列模板：可以是预置模板名，也可以是包含全部占位符的模板，\n 表示换行
+pub fn parse_bilingual_template(value: &str) -> anyhow::Result<String> {
+    if let Some((_, template)) = BILINGUAL_TEMPLATE_PRESETS.iter().find(|(name, _)| *name == value) {
+        return Ok(template.to_string());
+    }
+    let template = value.replace("\\n", "\n");
+    if BILINGUAL_TEMPLATE_PLACEHOLDERS.iter().any(|p| !template.contains(p)) {
+        return Err(anyhow::anyhow!(
+            "双语排列模板必须包含 {}，或使用预置模板: {}",
+            BILINGUAL_TEMPLATE_PLACEHOLDERS.join("、"),
+            BILINGUAL_TEMPLATE_PRESETS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join("/")
+        ));
+    }
+    Ok(template)
+}
+
+/// 模板中保存另一种语言标题的 trailer 字段名，如 "Translated-Title: {other_title}" 中的 Translated-Title
+pub fn bilingual_trailer_key(template: &str) -> Option<String> {
+    let regex = Regex::new(r"(?m)^([A-Za-z][A-Za-z0-9-]*):\s*\{other_title\}").unwrap();
+    regex.captures(template).map(|c| c[1].to_string())
+}
+
+/// 双语提交信息中另一种语言的标题和正文
+#[derive(Debug, Clone, PartialEq)]
+pub struct Translation {
+    pub title: String,
+    pub body: Option<String>,
+}
+
 pub struct CommitMessage {
     pub title: String,
     pub body: Option<String>,
-    pub marks: Vec<String>,
+    pub trailers: Vec<Trailer>,
+    /// 另一种语言的标题和正文，单语提交信息为 None
+    pub translation: Option<Translation>,
+}
+
+/// 双语排列模板中两种语言之间的分隔方式
+#[derive(Debug, PartialEq)]
+enum Layout {
+    /// 另一种语言的标题保存在 trailer 中，正文不拆分
+    Trailer(String),
+    /// 两部分之间有分隔行，如 separator 模板中的 ---
+    Separator(String),
+    /// 两部分直接相连，第二部分以与标题类型相同的标题行开始
+    Stacked,
+}
+
+/// 根据双语排列模板确定分隔方式，以及另一种语言是否排在前面
+fn bilingual_layout(template: &str) -> (Layout, bool) {
+    let position = |placeholder: &str| template.find(placeholder).unwrap_or(usize::MAX);
+    let other_first = position("{other_title}") < position("{title}");
+    if let Some(key) = bilingual_trailer_key(template) {
+        return (Layout::Trailer(key), other_first);
+    }
+
+    // 第一部分的正文与第二部分的标题之间的固定内容作为分隔行
+    let (first_body, second_title) = if other_first { ("{other_body}", "{title}") } else { ("{body}", "{other_title}") };
+    let separator = template.find(first_body)
+        .map(|start| &template[start + first_body.len()..])
+        .and_then(|rest| rest.find(second_title).map(|end| rest[..end].trim()))
+        .filter(|separator| !separator.is_empty() && !separator.contains('{'));
+    match separator {
+        Some(separator) => (Layout::Separator(separator.to_string()), other_first),
+        None => (Layout::Stacked, other_first),
+    }
+}
+
+/// 按模板的分隔方式从正文中拆出第二部分，返回第一部分的正文和第二部分的标题、正文
+fn split_translation(title: &str, body: &str, layout: &Layout) -> (Option<String>, Option<Translation>) {
+    let non_empty = |text: &str| (!text.trim().is_empty()).then(|| text.trim_start_matches('\n').trim_end().to_string());
+    let split_at = |first: &str, rest: &str| {
+        let rest = rest.trim_start();
+        let (other_title, other_body) = rest.split_once('\n').unwrap_or((rest, ""));
+        (non_empty(first), Some(Translation { title: other_title.trim_end().to_string(), body: non_empty(other_body) }))
+    };
+
+    match layout {
+        Layout::Trailer(_) => (non_empty(body), None),
+        Layout::Separator(separator) => {
+            let regex = Regex::new(&format!(r"(?m)^[ \t]*{}[ \t]*$", regex::escape(separator))).unwrap();
+            match regex.find(body) {
+                Some(m) if !body[m.end()..].trim().is_empty() => split_at(&body[..m.start()], &body[m.end()..]),
+                _ => (non_empty(body), None),
+            }
+        }
+        Layout::Stacked => {
+            // 第二部分的标题与标题使用相同的类型，且语言不同；标题没有类型前缀时不拆分
+            let Some(commit_type) = Regex::new(r"^([a-z]+)(?:\([^)]*\))?!?:\s*\S").unwrap().captures(title) else {
+                return (non_empty(body), None);
+            };
+            let title_regex = Regex::new(&format!(r"^{}(?:\([^)]*\))?!?:\s*\S", &commit_type[1])).unwrap();
+            let title_language = detection::detect(title);
+            let lines: Vec<&str> = body.lines().collect();
+            let start = (0..lines.len()).find(|&i| {
+                (i == 0 || lines[i - 1].trim().is_empty())
+                    && title_regex.is_match(lines[i])
+                    && detection::detect(lines[i]).is_some_and(|l| title_language.is_none_or(|t| !language::same_language(t, l)))
+            });
+            match start {
+                Some(i) => split_at(&lines[..i].join("\n"), &lines[i..].join("\n")),
+                None => (non_empty(body), None),
+            }
+        }
+    }
 }
 
 impl CommitMessage {
+    /// 按默认的双语排列解析提交信息
     pub fn parse(content: &str) -> Self {
-        let mark_regex = Regex::new(r"^[a-zA-Z-]+:\s*.+$").unwrap();
+        Self::parse_with(content, DEFAULT_BILINGUAL_TEMPLATE)
+    }
+
+    /// 按双语排列模板解析提交信息：根据模板中的分隔行或 trailer 拆出另一种语言的部分，
+    /// 模板中另一种语言排在前面时，第二部分作为默认语言
+    pub fn parse_with(content: &str, template: &str) -> Self {
         let comment_regex = Regex::new(r"^#.*$").unwrap();
-        let mut lines = content.lines().peekable();
-
-        // 获取第一个非注释行作为标题
-        let title = lines
-            .by_ref()
-            .find(|line| !comment_regex.is_match(line.trim()))
-            .unwrap_or("")
-            .to_string();
-
-        let mut body = Vec::new();
-        let mut marks = Vec::new();
-        let mut is_body = false;
-
-        while let Some(line) = lines.next() {
-            // 跳过注释行
-            if comment_regex.is_match(line.trim()) {
-                continue;
-            }
 
-            if line.trim().is_empty() {
-                if !is_body && body.is_empty() {
-                    continue;
-                }
-                is_body = true;
-                body.push(line.to_string());
-            } else if mark_regex.is_match(line) {
-                marks.push(line.to_string());
-            } else {
-                is_body = true;
-                body.push(line.to_string());
+        // 移除注释行，并跳过标题前的空行
+        let content = content.lines()
+            .filter(|line| !comment_regex.is_match(line.trim()))
+            .skip_while(|line| line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // 只有最后一段能作为 trailer，标题所在的第一段不会被识别
+        let (message, mut trailers) = trailer::split(&content);
+        let (title, body) = message.split_once('\n').unwrap_or((&message, ""));
+        let body = body.trim_start_matches(['\n', '\r']).trim_end();
+        let (layout, other_first) = bilingual_layout(template);
+        let (body, translation) = split_translation(title, body, &layout);
+
+        if let Layout::Trailer(key) = &layout {
+            if let Some(index) = trailers.iter().position(|t| t.is(key)) {
+                let translated = trailers.remove(index);
+                return CommitMessage {
+                    title: title.to_string(),
+                    body,
+                    trailers,
+                    translation: Some(Translation { title: translated.value, body: None }),
+                };
             }
         }
 
-        // 移除body末尾的空行
-        while body.last().map_or(false, |line| line.trim().is_empty()) {
-            body.pop();
+        match translation {
+            Some(translation) if other_first => CommitMessage {
+                title: translation.title,
+                body: translation.body,
+                trailers,
+                translation: Some(Translation { title: title.to_string(), body }),
+            },
+            translation => CommitMessage {
+                title: title.to_string(),
+                body,
+                trailers,
+                translation,
+            },
         }
+    }
 
-        // 移除 body 中的注释行
-        let body = if body.is_empty() {
-            None
-        } else {
-            Some(body
-                .into_iter()
-                .filter(|line| !comment_regex.is_match(line.trim()))
-                .collect::<Vec<_>>()
-                .join("\n"))
-        };
+    /// 是否已是双语提交信息：正文中包含另一种语言的部分，或带有模板中保存译文的 trailer
+    pub fn is_bilingual(&self, template: &str) -> bool {
+        self.translation.is_some()
+            || bilingual_trailer_key(template).is_some_and(|key| self.trailers.iter().any(|t| t.is(&key)))
+    }
 
-        CommitMessage {
-            title,
-            body,
-            marks,
-        }
+    /// 标题之后的全部正文，包括另一种语言的部分
+    pub fn full_body(&self) -> Option<String> {
+        let text = CommitMessage { title: String::new(), body: self.body.clone(), trailers: Vec::new(), translation: self.translation.clone() }
+            .format();
+        let text = text.trim();
+        (!text.is_empty()).then(|| text.to_string())
     }
 
+    /// 按默认的双语排列格式化
     pub fn format(&self) -> String {
-        let mut result = Vec::new();
-        result.push(self.title.clone());
+        self.format_with(DEFAULT_BILINGUAL_TEMPLATE)
+    }
 
-        if let Some(body) = &self.body {
-            if !body.is_empty() {
-                result.push(String::new());
-                result.push(body.clone());
+    /// 按双语排列模板格式化，单语提交信息忽略模板；模板末尾的 trailer 与已有 trailer 合并
+    pub fn format_with(&self, template: &str) -> String {
+        let Some(translation) = &self.translation else {
+            let mut result = self.title.clone();
+            if let Some(body) = &self.body {
+                if !body.is_empty() {
+                    result.push_str("\n\n");
+                    result.push_str(body);
+                }
             }
-        }
+            return trailer::join(&result, &self.trailers);
+        };
 
-        // 添加标记
-        if !self.marks.is_empty() {
-            if !result.last().map_or(false, |s| s.is_empty()) {
-                result.push(String::new());  // 添加空行分隔
-            }
-            result.extend(self.marks.clone());
+        let placeholder = Regex::new(r"\{(title|body|other_title|other_body)\}").unwrap();
+        let rendered = placeholder.replace_all(template, |c: &regex::Captures| match &c[1] {
+            "title" => self.title.clone(),
+            "body" => self.body.clone().unwrap_or_default(),
+            "other_title" => translation.title.clone(),
+            _ => translation.body.clone().unwrap_or_default(),
+        });
+        // 去掉空正文留下的多余空行
+        let rendered = Regex::new(r"\n\s*\n(\s*\n)+").unwrap().replace_all(rendered.trim(), "\n\n");
+
+        let (text, mut trailers) = trailer::split(&rendered);
+        for t in &self.trailers {
+            trailers.push(t.clone());
         }
-
-        result.join("\n")
+        trailer::join(&text, &trailers)
     }
 }
 
+use crate::context;
+use crate::gerrit;
+use crate::glossary;
+use crate::history;
+use crate::lint;
+use crate::output;
+use crate::prompt;
+use crate::split;
+use crate::squash;
+use crate::wrap;
 use crate::review;
-use dialoguer::Confirm;
 use log::{debug, info};
 use std::process::Command;
 
-pub async fn generate_commit_message(
-    commit_type: Option<String>,
-    message: Option<String>,
-    auto_add: bool,
-    amend: bool,
-    no_review: bool,
-    no_translate: bool,
-    mut only_chinese: bool,
-    mut only_english: bool,
-    no_influence: bool,
-    no_log: bool,
-    issues: Option<String>,
-) -> anyhow::Result<()> {
-    // 加载配置，如果指定了参数则使用参数值，否则使用配置中的默认值
-    if let Ok(config) = config::Config::load() {
-        if !only_chinese && !only_english {
-            only_chinese = config.only_chinese;
-            only_english = config.only_english;
-        }
-    }
+/// commit 子命令的选项
+#[derive(Debug, Default)]
+pub struct CommitOptions {
+    pub commit_type: Option<String>,
+    pub message: Option<String>,  // 用户对本次改动的描述
+    pub auto_add: bool,  // 先执行 git add -u
+    pub amend: bool,
+    pub no_review: bool,
+    pub no_translate: bool,
+    pub languages: Vec<String>,  // 提交信息的语言，为空时使用配置
+    pub no_influence: bool,
+    pub no_log: bool,
+    pub issues: Option<String>,
+    pub fetch_issues: bool,  // 获取关联 issue 的标题和描述
+    pub split: bool,  // 按改动拆分为多个提交
+    pub signoff: bool,
+    pub co_authors: Vec<String>,
+    pub print: Option<output::PrintOptions>,  // 只输出提交信息，不执行提交
+}
 
-    // 处理语言选项冲突：only_english 优先级最高
-    if only_english {
-        only_chinese = false;
-    } else if only_chinese {
-        only_english = false;
-    }
+pub async fn generate_commit_message(options: CommitOptions) -> anyhow::Result<()> {
+    let CommitOptions {
+        commit_type,
+        message,
+        auto_add,
+        amend,
+        no_review,
+        no_translate,
+        languages,
+        no_influence,
+        no_log,
+        issues,
+        fetch_issues,
+        split,
+        signoff,
+        co_authors,
+        print,
+    } = options;
 
     // 如果指定了 -a 参数，先执行 git add -u
     if auto_add {
@@ -768,9 +931,16 @@ pub async fn generate_commit_message(
         std::env::set_var("GIT_COMMIT_HELPER_NO_TRANSLATE", "1");
     }
 
+    if split && amend {
+        return Err(anyhow::anyhow!("--split 不能与 --amend 同时使用"));
+    }
+    if split && print.is_some() {
+        return Err(anyhow::anyhow!("--split 不能与 --dry-run 同时使用"));
+    }
+
     // 根据是否是 amend 模式选择不同的 diff
     let diff = if amend {
-        println!("正在分析上一次提交的更改内容...");
+        eprintln!("正在分析上一次提交的更改内容...");
         git::get_last_commit_diff()?
     } else {
         get_staged_diff()?
@@ -790,15 +960,17 @@ pub async fn generate_commit_message(
     if !amend && !no_review && config.ai_review {
         info!("正在进行代码审查...");
         if let Some(review) = review::review_changes(&config, no_review).await? {
-            println!("\n{}\n", review);
+            eprintln!("\n{}\n", review);
         }
     }
 
     // 设置环境变量标记跳过后续的代码审查
     std::env::set_var("GIT_COMMIT_HELPER_SKIP_REVIEW", "1");
 
-    // 确定语言模式并构建提示词，考虑是否包含测试建议
-    let language_mode = LanguageMode::determine(only_chinese, only_english);
+    // 确定语言模式并构建提示词，考虑是否包含测试建议；指定了语言参数时使用参数，否则使用配置中的默认值
+    let languages = resolve_languages(languages, &config)?;
+    let language_mode = LanguageMode::from_languages(&languages);
+    let glossary = glossary::current().commit_prompt(&languages);
     let include_test_suggestions = !no_influence;
     let include_log = !no_log;
     
@@ -815,12 +987,45 @@ pub async fn generate_commit_message(
         (None, None)
     };
     
+    let style_examples = if config.commit_style.enabled {
+        history::style_examples(&config.commit_style, &get_changed_files(amend)?)
+    } else {
+        Vec::new()
+    };
+
+    let issue_refs = collect_issue_refs(issues.as_deref(), &config, amend)?;
+    let mut trailers = issue_trailers(&issue_refs);
+    trailers.extend(collect_trailers(&config, signoff, &co_authors)?);
+
+    // 拆分模式下每组只包含部分改动，整体的上下文会误导生成结果
+    let repo_context = if split {
+        None
+    } else {
+        context::build_context(amend, config.context_budget)
+    };
+
+    // 获取 --issues 中关联 issue 的标题和描述
+    let issue_details = match issues.as_deref() {
+        Some(issues_str) if fetch_issues || config.issue_context.enabled => {
+            let sources = issue::IssueRegistry::new(&config.issue_trackers, &config.jira).sources(issues_str);
+            issue::fetch_details(&sources, &config.issue_context, config.timeout_seconds).await
+        }
+        _ => Vec::new(),
+    };
+
     let prompt = build_prompt(
-        language_mode, 
-        message.as_deref(), 
-        include_test_suggestions, 
+        language_mode,
+        include_test_suggestions,
         include_log,
-        original_message.as_deref()
+        &PromptExtras {
+            user_message: message.as_deref(),
+            original_message: original_message.as_deref(),
+            style_examples: &style_examples,
+            context: repo_context.as_deref(),
+            issues: &issue_details,
+            glossary: glossary.as_deref(),
+            ..Default::default()
+        },
     );
 
     debug!("生成的提示信息：\n{}", prompt);
@@ -830,83 +1035,55 @@ pub async fn generate_commit_message(
     let translator = ai_service::create_translator_for_service(service).await?;
 
     if amend {
-        println!("\n正在基于上一次提交的更改生成新的提交信息...");
+        eprintln!("\n正在基于上一次提交的更改生成新的提交信息...");
         // 显示原提交信息供参考
         if let Some(ref original_msg) = original_message {
-            println!("原提交信息:");
-            println!("----------------------------------------");
-            println!("{}", original_msg.trim());
-            println!("----------------------------------------\n");
+            eprintln!("原提交信息:");
+            eprintln!("----------------------------------------");
+            eprintln!("{}", original_msg.trim());
+            eprintln!("----------------------------------------\n");
         }
     } else {
-        println!("\n正在生成提交信息建议...");
+        eprintln!("\n正在生成提交信息建议...");
     }
 
-    let mut message = translator.chat(&prompt, &diff).await?
-        .trim_start_matches("[NO_TRANSLATE]")
-        .trim_start_matches("、、、plaintext")
-        .trim()
-        .to_string();
-
-    // 如果提供了具体的type，确保使用该type
-    if let Some(t) = commit_type {
-        message = ensure_commit_type(&message, &[t]);
+    if split {
+        return commit_in_groups(translator.as_ref(), &config, &prompt, commit_type.as_deref(), &trailers).await;
     }
 
-    // 处理换行
-    let mut content = message.lines().map(|line| {
-        if line.trim().is_empty() {
-            line.to_string()
-        } else {
-            git::wrap_text(line, 72)
-        }
-    }).collect::<Vec<_>>().join("\n");
+    let mut content = generate_content(translator.as_ref(), &config, &prompt, &diff, commit_type.as_deref()).await?;
 
-    // 如果指定了 issues 参数，添加引用字段
-    if let Some(issues_str) = issues {
-        match parse_issue_reference(&issues_str) {
-            Ok(reference) => {
-                // 在提交信息末尾添加空行和引用字段
-                if !content.ends_with('\n') {
-                    content.push('\n');
-                }
-                content.push('\n');
-                content.push_str(&reference);
-            }
-            Err(e) => {
-                eprintln!("警告: 解析 issues 参数失败: {}", e);
-            }
-        }
-    }
+    // 添加 issue 引用、Signed-off-by 等 trailer 字段
+    content = trailer::apply(&content, trailers);
 
-    // 在 amend 模式下，保留原提交中所有未被新内容覆盖的标记字段
+    // 在 amend 模式下，保留原提交中所有未被新内容覆盖的 trailer 字段
     // （Change-Id 须保持在最后，由 append_change_id 单独处理）
     if amend {
         if let Some(ref orig_msg) = original_message {
-            let orig_commit = CommitMessage::parse(orig_msg);
-            let marks_to_add: Vec<String> = orig_commit.marks.iter()
-                .filter(|mark| {
-                    let mark_key = mark.split(':').next().unwrap_or("").trim().to_lowercase();
-                    mark_key != "change-id" && !content.lines().any(|line| {
-                        line.trim().split(':').next()
-                            .map_or(false, |k| k.trim().to_lowercase() == mark_key)
-                    })
-                })
-                .cloned()
-                .collect();
-            if !marks_to_add.is_empty() {
-                if !content.ends_with('\n') {
-                    content.push('\n');
-                }
-                content.push('\n');
-                content.push_str(&marks_to_add.join("\n"));
-            }
+            let preserved = preserved_trailers(&CommitMessage::parse(orig_msg), &content);
+            content = trailer::apply(&content, preserved);
         }
         if let Some(change_id) = original_change_id {
             content = append_change_id(&content, &change_id);
         }
     }
 
+    // Gerrit 仓库的新提交需要 Change-Id
+    if !content.contains("Change-Id:") && gerrit::needs_change_id(&config) {
+        let change_id = gerrit::generate_change_id(&content, amend)?;
+        content = append_change_id(&content, &change_id);
+    }
+
+    // 仅输出提交信息，不执行提交
+    if let Some(options) = print {
+        let problems = lint::lint_text(&content, &config.lint);
+        if !problems.is_empty() {
+            eprintln!("提交信息检查结果:");
+            eprint!("{}", lint::format_problems(&problems));
+        }
+        return output::print_message(&content, &config.bilingual_template, &options);
+    }
+
     // 预览生成的提交信息
     if amend {
         println!("\n生成的修改后提交信息预览:");
@@ -917,6 +1094,12 @@ pub async fn generate_commit_message(
     println!("{}", content);
     println!("----------------------------------------");
 
+    let problems = lint::lint_text(&content, &config.lint);
+    if !problems.is_empty() {
+        println!("提交信息检查结果:");
+        print!("{}", lint::format_problems(&problems));
+    }
+
     // 询问用户是否确认提交
     let prompt_text = if amend {
         "是否使用此提交信息修改上一次提交？"
@@ -924,11 +1107,7 @@ pub async fn generate_commit_message(
         "是否使用此提交信息？"
     };
 
-    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt(prompt_text)
-        .default(true)
-        .interact()?
-    {
+    if !prompt::confirm(prompt_text, true)? {
         if amend {
             println!("已取消修改上一次提交");
         } else {
@@ -967,6 +1146,225 @@ pub async fn generate_commit_message(
     Ok(())
 }
 
+/// 确定提交信息使用的语言：参数优先，否则使用配置中的默认值
+fn resolve_languages(languages: Vec<String>, config: &config::Config) -> anyhow::Result<Vec<String>> {
+    if languages.is_empty() {
+        language::normalize_list(&config.commit_languages())
+    } else {
+        language::normalize_list(&languages)
+    }
+}
+
+/// 为提交范围生成一条 squash 合并的提交信息并输出
+pub async fn generate_squash_message(
+    range: &str,
+    languages: Vec<String>,
+    no_influence: bool,
+    no_log: bool,
+    print: output::PrintOptions,
+) -> anyhow::Result<()> {
+    let config = config::Config::load()?;
+    let source = squash::collect(range)?;
+    eprintln!("正在合并 {} 个提交的信息...", source.messages.len());
+
+    // 与 commit 子命令一致：参数优先，其次使用配置中的语言设置
+    let languages = resolve_languages(languages, &config)?;
+    let glossary = glossary::current().commit_prompt(&languages);
+    let prompt = build_prompt(
+        LanguageMode::from_languages(&languages),
+        !no_influence,
+        !no_log,
+        &PromptExtras {
+            squashed_messages: &source.messages,
+            glossary: glossary.as_deref(),
+            ..Default::default()
+        },
+    );
+    debug!("生成的提示信息：\n{}", prompt);
+
+    let service = config.get_default_service()?;
+    let translator = ai_service::create_translator_for_service(service).await?;
+    let content = generate_content(translator.as_ref(), &config, &prompt, &source.diff, None).await?;
+    let content = trailer::apply(&content, source.trailers);
+
+    let problems = lint::lint_text(&content, &config.lint);
+    if !problems.is_empty() {
+        eprintln!("提交信息检查结果:");
+        eprint!("{}", lint::format_problems(&problems));
+    }
+    output::print_message(&content, &config.bilingual_template, &print)
+}
+
+/// 调用 AI 生成提交信息，并完成检查修正、类型校正和换行处理
+async fn generate_content(
+    translator: &dyn ai_service::Translator,
+    config: &config::Config,
+    prompt: &str,
+    diff: &str,
+    commit_type: Option<&str>,
+) -> anyhow::Result<String> {
+    let message = translator.chat(prompt, diff).await?
+        .trim_start_matches("[NO_TRANSLATE]")
+        .trim_start_matches("、、、plaintext")
+        .trim()
+        .to_string();
+
+    // 检查生成的提交信息，未通过时请求 AI 自动修正
+    let mut message = fix_lint_problems(translator, &config.lint, message).await?;
+
+    // 如果提供了具体的type，确保使用该type
+    if let Some(t) = commit_type {
+        message = ensure_commit_type(&message, &[t.to_string()]);
+    }
+
+    // 处理换行
+    Ok(wrap::wrap_message(&message, wrap::MAX_LINE_WIDTH))
+}
+
+/// 将 issue 引用转换为 trailer 字段
+fn issue_trailers(refs: &[IssueRef]) -> Vec<Trailer> {
+    if refs.is_empty() {
+        return Vec::new();
+    }
+    issue::format_refs(refs)
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .map(|(key, value)| Trailer::new(key, value))
+        .collect()
+}
+
+/// 根据命令行参数和配置收集需要添加的 trailer 字段
+fn collect_trailers(config: &config::Config, signoff: bool, co_authors: &[String]) -> anyhow::Result<Vec<Trailer>> {
+    let mut trailers = Vec::new();
+    for line in &config.trailers.always {
+        match line.split_once(':') {
+            Some((key, value)) if !key.trim().is_empty() && !value.trim().is_empty() => {
+                trailers.push(Trailer::new(key.trim(), value.trim()));
+            }
+            _ => eprintln!("警告: 忽略格式错误的 trailer 配置 {}，应为 \"Key: value\"", line),
+        }
+    }
+    for alias in co_authors {
+        trailers.push(trailer::resolve_co_author(alias, &config.trailers.co_authors)?);
+    }
+    if signoff || config.trailers.signoff {
+        trailers.push(trailer::signoff()?);
+    }
+    Ok(trailers)
+}
+
+/// 拆分模式：由 AI 将暂存的改动分组，确认后逐组暂存并提交
+async fn commit_in_groups(
+    translator: &dyn ai_service::Translator,
+    config: &config::Config,
+    prompt: &str,
+    commit_type: Option<&str>,
+    trailers: &[Trailer],
+) -> anyhow::Result<()> {
+    let mut plan = split::SplitPlan::new(&split::get_staged_patch()?);
+    if plan.units.is_empty() {
+        return Err(anyhow::anyhow!("没有已暂存的改动，请先使用 git add 添加改动"));
+    }
+
+    println!("\n正在分析改动并生成拆分方案...");
+    plan.propose_groups(translator).await?;
+    if !plan.confirm_groups()? {
+        println!("已取消提交");
+        return Ok(());
+    }
+
+    let mut messages = Vec::new();
+    for (i, group) in plan.groups.iter().enumerate() {
+        println!("\n正在为分组 {}/{} 生成提交信息...", i + 1, plan.groups.len());
+        let content = generate_content(translator, config, prompt, &plan.patch_for(&group.units), commit_type).await?;
+        messages.push(trailer::apply(&content, trailers.iter().cloned()));
+    }
+
+    println!("\n生成的提交信息预览:");
+    for (i, content) in messages.iter().enumerate() {
+        println!("---------------- 提交 {}/{} ----------------", i + 1, messages.len());
+        println!("{}", content);
+        let problems = lint::lint_text(content, &config.lint);
+        if !problems.is_empty() {
+            println!("提交信息检查结果:");
+            print!("{}", lint::format_problems(&problems));
+        }
+    }
+    println!("----------------------------------------");
+
+    if !prompt::confirm(&format!("是否按此方案创建 {} 个提交？", messages.len()), true)? {
+        println!("已取消提交");
+        return Ok(());
+    }
+
+    let needs_change_id = gerrit::needs_change_id(config);
+    split::unstage_all()?;
+    for (i, (group, content)) in plan.groups.iter().zip(&messages).enumerate() {
+        let result = split::stage_patch(&plan.patch_for(&group.units)).and_then(|_| {
+            // Change-Id 依赖暂存区的 tree 和父提交，需在每组暂存后生成
+            let content = if needs_change_id {
+                append_change_id(content, &gerrit::generate_change_id(content, false)?)
+            } else {
+                content.clone()
+            };
+            let status = Command::new("git")
+                .arg("commit")
+                .arg("-m")
+                .arg(content)
+                .status()?;
+            if !status.success() {
+                return Err(anyhow::anyhow!("git commit 命令执行失败"));
+            }
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            // 将尚未提交的改动重新放回暂存区
+            let rest: Vec<usize> = plan.groups[i..].iter()
+                .flat_map(|g| g.units.iter().copied())
+                .collect();
+            let restored = split::unstage_all().and_then(|_| split::stage_patch(&plan.patch_for(&rest)));
+            if let Err(restore_error) = restored {
+                eprintln!("警告: 恢复暂存区失败，请手动检查: {}", restore_error);
+            }
+            std::env::remove_var("GIT_COMMIT_HELPER_SKIP_REVIEW");
+            std::env::remove_var("GIT_COMMIT_HELPER_NO_TRANSLATE");
+            return Err(anyhow::anyhow!("第 {} 个提交失败，剩余改动已重新暂存: {}", i + 1, e));
+        }
+        println!("提交 {}/{} 成功！", i + 1, messages.len());
+    }
+
+    std::env::remove_var("GIT_COMMIT_HELPER_SKIP_REVIEW");
+    std::env::remove_var("GIT_COMMIT_HELPER_NO_TRANSLATE");
+    println!("拆分提交完成！");
+    Ok(())
+}
+
+/// 对 AI 生成的提交信息执行检查，存在问题时将问题列表发给 AI 请求修正
+async fn fix_lint_problems(translator: &dyn ai_service::Translator, lint_config: &config::LintConfig, mut message: String) -> anyhow::Result<String> {
+    for attempt in 1..=lint_config.fix_attempts {
+        let problems = lint::lint_text(&message, lint_config);
+        if problems.is_empty() {
+            break;
+        }
+
+        info!("生成的提交信息未通过检查，正在请求 AI 修正（第 {} 次）", attempt);
+        eprint!("{}", lint::format_problems(&problems));
+        eprintln!("正在请求 AI 修正提交信息...");
+
+        let fixed = translator.chat(&lint::build_fix_prompt(&problems), &message).await?;
+        let fixed = fixed
+            .trim_start_matches("[NO_TRANSLATE]")
+            .trim_start_matches("、、、plaintext")
+            .trim();
+        if fixed.is_empty() {
+            break;
+        }
+        message = fixed.to_string();
+    }
+    Ok(message)
+}
+
 #[allow(dead_code)]
 pub async fn generate_commit_suggestion(commit_types: &[String], user_description: Option<String>) -> anyhow::Result<String> {
     let config = crate::config::Config::load()?;
@@ -980,7 +1378,7 @@ pub async fn generate_commit_suggestion(commit_types: &[String], user_descriptio
         None => get_staged_diff()?
     };
 
-    let message = translator.translate(&prompt, &config::TranslateDirection::ChineseToEnglish).await?.to_string();
+    let message = translator.translate(&prompt, &LanguagePair::default()).await?.to_string();
 
     // 移除各种 AI 返回的元信息标记
     let message = message
@@ -1009,6 +1407,28 @@ fn get_staged_diff() -> anyhow::Result<String> {
     Ok(String::from_utf8(output.stdout)?)
 }
 
+/// 获取本次提交涉及的文件列表，amend 模式下为上一次提交的文件
+fn get_changed_files(amend: bool) -> anyhow::Result<Vec<String>> {
+    let output = if amend {
+        Command::new("git")
+            .args(["diff-tree", "--no-commit-id", "--name-only", "-r", "HEAD"])
+            .output()?
+    } else {
+        Command::new("git")
+            .args(["diff", "--cached", "--name-only"])
+            .output()?
+    };
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("获取改动文件列表失败"));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.to_string())
+        .collect())
+}
+
 fn ensure_commit_type(message: &str, commit_types: &[String]) -> String {
     let first_line = message.lines().next().unwrap_or_default();
 
@@ -1034,7 +1454,7 @@ mod tests {
     #[test]
     fn test_parse_github_issue_url() {
         let url = "https://github.com/zccrs/git-commit-helper/issues/123";
-        let result = parse_github_issue(url).unwrap();
+        let result = parse_issue_reference(url).unwrap();
         // 由于无法在测试环境中获取git remote，这里只测试格式解析
         assert!(result.contains("#123") || result.contains("zccrs/git-commit-helper#123"));
     }
@@ -1042,21 +1462,21 @@ mod tests {
     #[test]
     fn test_parse_pms_bug_link() {
         let url = "https://pms.uniontech.com/bug-view-320461.html";
-        let result = parse_pms_link(url).unwrap();
+        let result = parse_issue_reference(url).unwrap();
         assert_eq!(result, "PMS: BUG-320461");
     }
 
     #[test]
     fn test_parse_pms_task_link() {
         let url = "https://pms.uniontech.com/task-view-374223.html";
-        let result = parse_pms_link(url).unwrap();
+        let result = parse_issue_reference(url).unwrap();
         assert_eq!(result, "PMS: TASK-374223");
     }
 
     #[test]
     fn test_parse_pms_story_link() {
         let url = "https://pms.uniontech.com/story-view-38949.html";
-        let result = parse_pms_link(url).unwrap();
+        let result = parse_issue_reference(url).unwrap();
         assert_eq!(result, "PMS: STORY-38949");
     }
 
@@ -1176,17 +1596,24 @@ mod tests {
         assert!(result.ends_with("Change-Id: I1234567890abcdef1234567890abcdef12345678"));
     }
 
+    #[test]
+    fn test_append_change_id_after_signed_off_by() {
+        let message = "feat: add new feature\n\nThis is a commit message\n\nSigned-off-by: A <a@b.com>\n";
+        let result = append_change_id(message, "I1234");
+        assert_eq!(result, "feat: add new feature\n\nThis is a commit message\n\nSigned-off-by: A <a@b.com>\nChange-Id: I1234");
+
+        let result = append_change_id("feat: title only", "I1234");
+        assert_eq!(result, "feat: title only\n\nChange-Id: I1234");
+    }
+
     // 测试从原提交消息中提取所有需保留的标记（排除 Change-Id）
     #[test]
     fn test_extract_issue_marks_from_original_commit() {
         let original = "fix: some bug\n\nDescription here\n\nFixes: #123\nPMS: BUG-456\nLog: Fix critical bug\nChange-Id: Iabc123\n";
         let orig_commit = CommitMessage::parse(original);
-        let marks_to_preserve: Vec<String> = orig_commit.marks.iter()
-            .filter(|mark| {
-                let mark_key = mark.split(':').next().unwrap_or("").trim().to_lowercase();
-                mark_key != "change-id"
-            })
-            .cloned()
+        let marks_to_preserve: Vec<String> = preserved_trailers(&orig_commit, "")
+            .iter()
+            .map(|t| t.to_string())
             .collect();
         assert_eq!(marks_to_preserve.len(), 3);
         assert!(marks_to_preserve.contains(&"Fixes: #123".to_string()));
@@ -1204,15 +1631,9 @@ mod tests {
 
         // 新内容已包含 Fixes: 和 Log:
         let new_content = "fix: improved bug fix\n\nBetter description\n\nFixes: #123\nLog: Fix bug";
-        let marks_to_add: Vec<String> = orig_commit.marks.iter()
-            .filter(|mark| {
-                let mark_key = mark.split(':').next().unwrap_or("").trim().to_lowercase();
-                mark_key != "change-id" && !new_content.lines().any(|line| {
-                    line.trim().split(':').next()
-                        .map_or(false, |k| k.trim().to_lowercase() == mark_key)
-                })
-            })
-            .cloned()
+        let marks_to_add: Vec<String> = preserved_trailers(&orig_commit, new_content)
+            .iter()
+            .map(|t| t.to_string())
             .collect();
         // 不应重复添加
         assert!(marks_to_add.is_empty());
@@ -1226,15 +1647,9 @@ mod tests {
 
         // 新内容不含任何标记
         let new_content = "fix: improved bug fix\n\nBetter description";
-        let marks_to_add: Vec<String> = orig_commit.marks.iter()
-            .filter(|mark| {
-                let mark_key = mark.split(':').next().unwrap_or("").trim().to_lowercase();
-                mark_key != "change-id" && !new_content.lines().any(|line| {
-                    line.trim().split(':').next()
-                        .map_or(false, |k| k.trim().to_lowercase() == mark_key)
-                })
-            })
-            .cloned()
+        let marks_to_add: Vec<String> = preserved_trailers(&orig_commit, new_content)
+            .iter()
+            .map(|t| t.to_string())
             .collect();
         assert_eq!(marks_to_add.len(), 3);
         assert!(marks_to_add.contains(&"Fixes: #123".to_string()));
@@ -1243,4 +1658,87 @@ mod tests {
         // Change-Id 不应被包含（由 append_change_id 处理）
         assert!(!marks_to_add.iter().any(|m| m.to_lowercase().starts_with("change-id:")));
     }
+
+    #[test]
+    fn test_language_mode_templates() {
+        let mode = LanguageMode::from_languages(&["en".to_string(), "zh".to_string()]);
+        assert_eq!(mode.template(true, true), BILINGUAL_PROMPT_TEMPLATE);
+        assert!(LanguageMode::from_languages(&["zh-CN".to_string()]).is_chinese_only());
+        assert!(!LanguageMode::from_languages(&["zh-TW".to_string()]).is_chinese_only());
+
+        let japanese = LanguageMode::Single("ja".to_string()).template(true, false);
+        assert!(japanese.contains("generate a commit message in Japanese only"));
+        assert!(japanese.contains("Detailed explanation in Japanese"));
+        assert!(!japanese.contains("DO NOT include any Chinese content"));
+
+        let reversed = LanguageMode::Bilingual("zh".to_string(), "en".to_string()).template(false, true);
+        assert!(reversed.contains("1. First line in Chinese: type: message"));
+        assert!(reversed.contains("English title and explanation (translate the Chinese content)"));
+        assert!(reversed.ends_with("write the first part in Chinese and the second part in English, keeping the type prefix and the \"Log:\"/\"Influence:\" field names in English."));
+    }
+
+    #[test]
+    fn test_bilingual_layout() {
+        let content = "fix: handle empty input\n\n1. Return early\n\nfix: 修复空输入\n\n1. 提前返回\n\nSigned-off-by: A <a@b.c>";
+        let msg = CommitMessage::parse(content);
+        assert_eq!(msg.body.as_deref(), Some("1. Return early"));
+        assert_eq!(msg.translation, Some(Translation {
+            title: "fix: 修复空输入".to_string(),
+            body: Some("1. 提前返回".to_string()),
+        }));
+        assert_eq!(msg.format(), content);
+        assert_eq!(msg.full_body().as_deref(), Some("1. Return early\n\nfix: 修复空输入\n\n1. 提前返回"));
+
+        let template = |name| parse_bilingual_template(name).unwrap();
+        assert_eq!(msg.format_with(&template("other-first")),
+            "fix: 修复空输入\n\n1. 提前返回\n\nfix: handle empty input\n\n1. Return early\n\nSigned-off-by: A <a@b.c>");
+        assert_eq!(msg.format_with(&template("separator")),
+            "fix: handle empty input\n\n1. Return early\n\n---\n\nfix: 修复空输入\n\n1. 提前返回\n\nSigned-off-by: A <a@b.c>");
+
+        let trailer_layout = msg.format_with(&template("trailer"));
+        assert_eq!(trailer_layout, "fix: handle empty input\n\n1. Return early\n\n1. 提前返回\n\nTranslated-Title: fix: 修复空输入\nSigned-off-by: A <a@b.c>");
+        let reparsed = CommitMessage::parse(&trailer_layout);
+        assert!(reparsed.translation.is_none());
+        assert!(reparsed.is_bilingual(&template("trailer")));
+        assert!(!reparsed.is_bilingual(DEFAULT_BILINGUAL_TEMPLATE));
+
+        let custom = template("{other_title}\\n\\n{title}\\n\\n{body}\\n\\n{other_body}");
+        assert_eq!(custom, "{other_title}\n\n{title}\n\n{body}\n\n{other_body}");
+        let no_body = CommitMessage { body: None, ..CommitMessage::parse(content) };
+        assert_eq!(no_body.format_with(&custom), "fix: 修复空输入\n\nfix: handle empty input\n\n1. 提前返回\n\nSigned-off-by: A <a@b.c>");
+        assert!(parse_bilingual_template("{title}").is_err());
+        // 另一种语言的正文可能是作者的原文，不能省略
+        assert!(parse_bilingual_template("{other_title}\\n\\n{title}\\n\\n{body}").is_err());
+
+        let single = CommitMessage::parse("fix: 修复空输入\n\n1. 提前返回\n\nfix: 再修复一处");
+        assert!(single.translation.is_none());
+    }
+
+    #[test]
+    fn test_parse_with_template() {
+        let template = |name| parse_bilingual_template(name).unwrap();
+        let content = "fix: handle empty input\n\n1. Return early\n\nfix: 修复空输入\n\n1. 提前返回\n\nSigned-off-by: A <a@b.c>";
+        let msg = CommitMessage::parse(content);
+
+        // 各预置排列都能按模板解析回原来的内容
+        for name in ["stacked", "other-first", "separator", "trailer"] {
+            let formatted = msg.format_with(&template(name));
+            let reparsed = CommitMessage::parse_with(&formatted, &template(name));
+            assert_eq!(reparsed.title, "fix: handle empty input", "{}", name);
+            assert_eq!(reparsed.translation.as_ref().map(|t| t.title.as_str()), Some("fix: 修复空输入"), "{}", name);
+            assert_eq!(reparsed.format_with(&template(name)), formatted, "{}", name);
+        }
+
+        // 分隔行前后都按模板拆分，不会把分隔行留在正文中
+        let separated = CommitMessage::parse_with(&msg.format_with(&template("separator")), &template("separator"));
+        assert_eq!(separated.body.as_deref(), Some("1. Return early"));
+        assert_eq!(separated.translation.unwrap().body.as_deref(), Some("1. 提前返回"));
+
+        // 正文中以其他单词加冒号开头的行不是另一种语言的标题
+        let note = CommitMessage::parse("fix: 修复空输入\n\n1. 提前返回\n\nnote: the parser still needs a fuzz test");
+        assert!(note.translation.is_none());
+        assert_eq!(note.body.as_deref(), Some("1. 提前返回\n\nnote: the parser still needs a fuzz test"));
+        let separator_only = CommitMessage::parse_with("fix: 修复空输入\n\n1. 提前返回\n\nfix: handle empty input", &template("separator"));
+        assert!(separator_only.translation.is_none());
+    }
 }
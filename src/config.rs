@@ -2,6 +2,7 @@ use anyhow::{Context, Result};
 use dialoguer::{Confirm, Input};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::fs;
 use copilot_client::CopilotClient;
@@ -9,6 +10,8 @@ use copilot_client::get_github_token;
 use log::{debug, info, warn};
 use dialoguer::console::Term;
 use crate::ai_service;
+use crate::language::LanguagePair;
+use crate::prompt;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
@@ -22,15 +25,55 @@ pub struct Config {
     pub max_tokens: u64,  // 添加响应的最大 token
     #[serde(default)]
     pub gerrit: Option<GerritConfig>,  // Gerrit 配置
+    #[serde(default)]
+    pub gerrit_change_id: bool,  // 始终为新提交生成 Gerrit Change-Id（默认根据远程仓库自动判断）
     #[serde(default = "default_only_chinese")]
     pub only_chinese: bool,  // 是否默认只使用中文
     #[serde(default = "default_only_english")]
     pub only_english: bool,  // 是否默认只使用英文
-    #[serde(default = "default_translate_direction")]
-    pub translate_direction: TranslateDirection,  // 默认翻译方向
+    #[serde(default)]
+    pub languages: Vec<String>,  // 提交信息使用的语言（BCP-47 代码，最多两种，第一种在前），为空时根据 only_chinese/only_english 决定
+    #[serde(default)]
+    pub translate_direction: LanguagePair,  // 默认翻译方向
+    #[serde(default = "default_bilingual_template")]
+    pub bilingual_template: String,  // commit-msg hook 生成双语提交信息时的排列模板
+    #[serde(default)]
+    pub translation_memory: TranslationMemoryConfig,  // 复用历史译文的翻译记忆
+    #[serde(default = "default_review_language")]
+    pub review_language: String,  // 代码审查意见使用的语言，也是远程改动标题和描述翻译的目标语言
+    #[serde(default)]
+    pub lint: LintConfig,  // 提交信息检查规则
+    #[serde(default)]
+    pub commit_style: CommitStyleConfig,  // 从提交历史学习仓库的提交风格
+    #[serde(default = "default_context_budget")]
+    pub context_budget: usize,  // 提示词中仓库上下文的字符数上限，0 表示不附加
+    #[serde(default)]
+    pub branch_issues: BranchIssueConfig,  // 从分支名中识别 issue 引用
+    #[serde(default)]
+    pub issue_trackers: Vec<IssueTracker>,  // 自定义 issue 跟踪系统，优先于内置规则匹配
+    #[serde(default)]
+    pub jira: JiraConfig,  // Jira 主机和项目，配置后才识别 Jira 引用
+    #[serde(default)]
+    pub issue_context: IssueContextConfig,  // 获取关联 issue 的内容
+    #[serde(default)]
+    pub trailers: TrailerConfig,  // 提交信息末尾的 trailer 字段
+    #[serde(default)]
+    pub non_interactive: bool,  // 非交互模式，跳过所有确认提示并使用默认选项
 }
 
 // 添加默认值函数
+fn default_bilingual_template() -> String {
+    crate::commit::DEFAULT_BILINGUAL_TEMPLATE.to_string()
+}
+
+fn default_review_language() -> String {
+    "zh".to_string()
+}
+
+fn default_context_budget() -> usize {
+    2000
+}
+
 fn default_only_chinese() -> bool {
     false
 }
@@ -39,14 +82,227 @@ fn default_only_english() -> bool {
     false
 }
 
-fn default_translate_direction() -> TranslateDirection {
-    TranslateDirection::ChineseToEnglish
+
+/// 提交信息检查规则的级别，与 commitlint 的 0/1/2 对应
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Off,
+    Warning,
+    Error,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub enum TranslateDirection {
-    ChineseToEnglish,  // 中译英（默认）
-    EnglishToChinese,  // 英译中
+/// 单条检查规则的配置，value 的含义由具体规则决定（如长度上限、类型列表）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LintRuleConfig {
+    pub level: LintLevel,
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LintConfig {
+    #[serde(default = "default_lint_enabled")]
+    pub enabled: bool,  // 是否在生成提交信息和 commit-msg hook 中检查提交信息，默认关闭
+    #[serde(default = "default_lint_fix_attempts")]
+    pub fix_attempts: u32,  // AI 生成的信息未通过检查时，自动请求修正的次数
+    #[serde(default)]
+    pub rules: HashMap<String, LintRuleConfig>,  // 覆盖内置规则的默认配置
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_lint_enabled(),
+            fix_attempts: default_lint_fix_attempts(),
+            rules: HashMap::new(),
+        }
+    }
+}
+
+fn default_lint_enabled() -> bool {
+    false
+}
+
+fn default_lint_fix_attempts() -> u32 {
+    1
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitStyleConfig {
+    #[serde(default)]
+    pub enabled: bool,  // 是否将历史提交信息作为风格示例
+    #[serde(default = "default_style_sample_size")]
+    pub sample_size: usize,  // 示例数量
+    #[serde(default = "default_style_cache_ttl_hours")]
+    pub cache_ttl_hours: u64,  // 历史摘要缓存的有效期（小时）
+}
+
+impl Default for CommitStyleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_size: default_style_sample_size(),
+            cache_ttl_hours: default_style_cache_ttl_hours(),
+        }
+    }
+}
+
+fn default_style_sample_size() -> usize {
+    5
+}
+
+fn default_style_cache_ttl_hours() -> u64 {
+    24
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TranslationMemoryConfig {
+    #[serde(default = "default_translation_memory_enabled")]
+    pub enabled: bool,  // 是否启用翻译记忆
+    #[serde(default = "default_fuzzy_threshold")]
+    pub fuzzy_threshold: f64,  // 相似片段作为参考译文的最低相似度（0 到 1）
+    #[serde(default = "default_translation_memory_max_entries")]
+    pub max_entries: usize,  // 最多保存的条数，超出时删除最早的记忆
+}
+
+impl Default for TranslationMemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_translation_memory_enabled(),
+            fuzzy_threshold: default_fuzzy_threshold(),
+            max_entries: default_translation_memory_max_entries(),
+        }
+    }
+}
+
+fn default_translation_memory_enabled() -> bool {
+    true
+}
+
+fn default_fuzzy_threshold() -> f64 {
+    0.8
+}
+
+fn default_translation_memory_max_entries() -> usize {
+    5000
+}
+
+/// issue 跟踪系统规则：pattern 匹配 --issues 中的链接，format 生成 trailer 字段的值；
+/// pattern 中名为 repo 的捕获组与当前仓库一致时使用 local_format
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IssueTracker {
+    pub name: String,
+    pub pattern: String,
+    pub trailer: String,
+    pub format: String,
+    #[serde(default)]
+    pub local_format: Option<String>,
+    #[serde(default)]
+    pub api: Option<String>,  // 获取 issue 详情的接口地址，可引用 pattern 的捕获组
+    #[serde(default)]
+    pub title_pointer: Option<String>,  // 接口返回 JSON 中标题的位置（JSON Pointer），默认 /title
+    #[serde(default)]
+    pub body_pointer: Option<String>,  // 接口返回 JSON 中描述的位置，默认 /body
+    #[serde(default)]
+    pub token_env: Option<String>,  // 保存访问令牌的环境变量名
+}
+
+/// Jira 配置：配置 host 后识别该主机上的 browse 链接，配置 project_keys 后识别这些项目的 PROJ-123 形式编号
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct JiraConfig {
+    #[serde(default)]
+    pub host: Option<String>,  // Jira 主机名，如 jira.example.com
+    #[serde(default)]
+    pub project_keys: Vec<String>,  // 项目 key，如 PROJ、CORE
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IssueContextConfig {
+    #[serde(default)]
+    pub enabled: bool,  // 是否获取关联 issue 的标题和描述作为生成提交信息的参考
+    #[serde(default = "default_issue_cache_ttl_hours")]
+    pub cache_ttl_hours: u64,  // issue 信息缓存的有效期（小时）
+    #[serde(default = "default_issue_max_chars")]
+    pub max_chars: usize,  // 每个 issue 描述的最大字符数
+}
+
+impl Default for IssueContextConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cache_ttl_hours: default_issue_cache_ttl_hours(),
+            max_chars: default_issue_max_chars(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TrailerConfig {
+    #[serde(default)]
+    pub signoff: bool,  // 是否默认添加 Signed-off-by
+    #[serde(default)]
+    pub always: Vec<String>,  // 每次提交都添加的 trailer，格式为 "Key: value"
+    #[serde(default)]
+    pub co_authors: BTreeMap<String, String>,  // 合作者别名，值为 "Name <email>"
+}
+
+fn default_issue_cache_ttl_hours() -> u64 {
+    24
+}
+
+fn default_issue_max_chars() -> usize {
+    1500
+}
+
+/// 分支名识别规则，format 中可使用 $1、${name} 引用 pattern 的捕获组
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BranchIssuePattern {
+    pub pattern: String,
+    pub trailer: String,
+    pub format: String,
+}
+
+impl BranchIssuePattern {
+    fn new(pattern: &str, trailer: &str, format: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            trailer: trailer.to_string(),
+            format: format.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BranchIssueConfig {
+    #[serde(default = "default_branch_issues_enabled")]
+    pub enabled: bool,  // 是否从分支名中识别 issue 引用
+    #[serde(default = "default_branch_issue_patterns")]
+    pub patterns: Vec<BranchIssuePattern>,  // 识别规则，按顺序匹配
+}
+
+impl Default for BranchIssueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_branch_issues_enabled(),
+            patterns: default_branch_issue_patterns(),
+        }
+    }
+}
+
+fn default_branch_issues_enabled() -> bool {
+    true
+}
+
+pub fn default_branch_issue_patterns() -> Vec<BranchIssuePattern> {
+    vec![
+        // fix/1234-crash、feature/1234 等：当前仓库的 GitHub issue
+        BranchIssuePattern::new(r"^(?:fix|bugfix|hotfix|feat|feature|issue)/(\d+)(?:[-_]|$)", "Fixes", "#${1}"),
+        // BUG-5678、task-42 等：PMS 中的缺陷、任务和需求
+        BranchIssuePattern::new(r"(?i)\bbug-(\d+)", "PMS", "BUG-${1}"),
+        BranchIssuePattern::new(r"(?i)\btask-(\d+)", "PMS", "TASK-${1}"),
+        BranchIssuePattern::new(r"(?i)\bstory-(\d+)", "PMS", "STORY-${1}"),
+    ]
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -90,6 +346,12 @@ pub enum AIService {
     Qwen,    // 新增
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Config {
     pub fn new() -> Self {
         Self {
@@ -99,9 +361,23 @@ impl Config {
             timeout_seconds: default_timeout(),
             max_tokens: default_max_tokens(),
             gerrit: None,
+            gerrit_change_id: false,
             only_chinese: false,  // 默认关闭
             only_english: false,  // 默认关闭
-            translate_direction: default_translate_direction(),  // 默认中译英
+            translate_direction: LanguagePair::default(),  // 默认中译英
+            lint: LintConfig::default(),
+            commit_style: CommitStyleConfig::default(),
+            context_budget: default_context_budget(),
+            branch_issues: BranchIssueConfig::default(),
+            issue_trackers: Vec::new(),
+            jira: JiraConfig::default(),
+            issue_context: IssueContextConfig::default(),
+            trailers: TrailerConfig::default(),
+            non_interactive: false,
+            languages: Vec::new(),
+            bilingual_template: default_bilingual_template(),
+            translation_memory: TranslationMemoryConfig::default(),
+            review_language: default_review_language(),
         }
     }
 
@@ -125,10 +401,12 @@ impl Config {
     }
 
     pub async fn interactive_config() -> Result<()> {
+        prompt::require_interactive("运行配置向导")?;
         Box::pin(Self::interactive_config_impl()).await
     }
 
     pub async fn setup_gerrit(&mut self) -> Result<()> {
+        prompt::require_interactive("配置 Gerrit 认证")?;
         println!("\nGerrit 认证配置");
         println!("选择认证方式：");
         println!("1) 用户名密码");
@@ -254,7 +532,7 @@ impl Config {
                 .report(true)
                 .validate_with(|input: &String| -> Result<(), &str> {
                     match input.parse::<usize>() {
-                        Ok(n) if n >= 1 && n <= 7 => Ok(()),
+                        Ok(n) if (1..=7).contains(&n) => Ok(()),
                         _ => Err("请输入 1-7 之间的数字")
                     }
                 })
@@ -304,9 +582,23 @@ impl Config {
             timeout_seconds: default_timeout(),
             max_tokens: default_max_tokens(),
             gerrit: None,
+            gerrit_change_id: false,
             only_chinese: false,  // 默认关闭
             only_english: false,  // 默认关闭
-            translate_direction: default_translate_direction(),  // 默认中译英
+            translate_direction: LanguagePair::default(),  // 默认中译英
+            lint: LintConfig::default(),
+            commit_style: CommitStyleConfig::default(),
+            context_budget: default_context_budget(),
+            branch_issues: BranchIssueConfig::default(),
+            issue_trackers: Vec::new(),
+            jira: JiraConfig::default(),
+            issue_context: IssueContextConfig::default(),
+            trailers: TrailerConfig::default(),
+            non_interactive: false,
+            languages: Vec::new(),
+            bilingual_template: default_bilingual_template(),
+            translation_memory: TranslationMemoryConfig::default(),
+            review_language: default_review_language(),
         };
 
         // 确保配置目录存在
@@ -334,12 +626,26 @@ impl Config {
                 timeout_seconds: config.timeout_seconds,
                 max_tokens: config.max_tokens,
                 gerrit: None,
+                gerrit_change_id: false,
                 only_chinese: false,
                 only_english: false,
-                translate_direction: default_translate_direction(),
+                translate_direction: LanguagePair::default(),
+                lint: LintConfig::default(),
+                commit_style: CommitStyleConfig::default(),
+                context_budget: default_context_budget(),
+                branch_issues: BranchIssueConfig::default(),
+                issue_trackers: Vec::new(),
+                jira: JiraConfig::default(),
+                issue_context: IssueContextConfig::default(),
+                trailers: TrailerConfig::default(),
+                non_interactive: false,
+                languages: Vec::new(),
+                bilingual_template: default_bilingual_template(),
+                translation_memory: TranslationMemoryConfig::default(),
+                review_language: default_review_language(),
             };
             let translator = ai_service::create_translator(&test_config).await?;
-            match translator.translate("这是一个测试消息，用于验证翻译功能是否正常。", &TranslateDirection::ChineseToEnglish).await {
+            match translator.translate("这是一个测试消息，用于验证翻译功能是否正常。", &LanguagePair::default()).await {
                 Ok(result) => {
                     println!("\n测试结果:");
                     println!("原文: 这是一个测试消息，用于验证翻译功能是否正常。");
@@ -395,6 +701,7 @@ impl Config {
     }
 
     pub async fn add_service(&mut self, service: AIService) -> Result<()> {
+        prompt::require_interactive("添加 AI 服务")?;
         Box::pin(self.add_service_impl(service)).await
     }
 
@@ -509,14 +816,28 @@ impl Config {
                 timeout_seconds: self.timeout_seconds,
                 max_tokens: self.max_tokens,
                 gerrit: None,
+                gerrit_change_id: false,
                 only_chinese: false,
                 only_english: false,
-                translate_direction: default_translate_direction(),
+                translate_direction: LanguagePair::default(),
+                lint: LintConfig::default(),
+                commit_style: CommitStyleConfig::default(),
+                context_budget: default_context_budget(),
+                branch_issues: BranchIssueConfig::default(),
+                issue_trackers: Vec::new(),
+                jira: JiraConfig::default(),
+                issue_context: IssueContextConfig::default(),
+                trailers: TrailerConfig::default(),
+                non_interactive: false,
+                languages: Vec::new(),
+                bilingual_template: default_bilingual_template(),
+                translation_memory: TranslationMemoryConfig::default(),
+                review_language: default_review_language(),
             };
             let translator = ai_service::create_translator(&test_config).await?;
             let text = "这是一个测试消息，用于验证翻译功能是否正常。";
             debug!("开始发送翻译请求");
-            match translator.translate(text, &TranslateDirection::ChineseToEnglish).await {
+            match translator.translate(text, &LanguagePair::default()).await {
                 Ok(result) => {
                     debug!("收到翻译响应");
                     println!("\n测试结果:");
@@ -581,6 +902,7 @@ impl Config {
     }
 
     pub async fn edit_service(&mut self) -> Result<()> {
+        prompt::require_interactive("修改 AI 服务")?;
         if self.services.is_empty() {
             return Err(anyhow::anyhow!("没有可编辑的 AI 服务"));
         }
@@ -615,6 +937,7 @@ impl Config {
     }
 
     pub async fn remove_service(&mut self) -> Result<()> {
+        prompt::require_interactive("删除 AI 服务")?;
         if self.services.is_empty() {
             return Err(anyhow::anyhow!("没有可删除的 AI 服务"));
         }
@@ -649,6 +972,7 @@ impl Config {
     }
 
     pub async fn set_default_service(&mut self) -> Result<()> {
+        prompt::require_interactive("设置默认 AI 服务")?;
         if self.services.is_empty() {
             return Err(anyhow::anyhow!("没有可选择的 AI 服务"));
         }
@@ -883,6 +1207,19 @@ impl Config {
         })
     }
 
+    /// 提交信息使用的语言，未设置 languages 时根据 only_chinese/only_english 决定，默认英文、中文双语
+    pub fn commit_languages(&self) -> Vec<String> {
+        if !self.languages.is_empty() {
+            self.languages.clone()
+        } else if self.only_english {
+            vec!["en".to_string()]
+        } else if self.only_chinese {
+            vec!["zh".to_string()]
+        } else {
+            vec!["en".to_string(), "zh".to_string()]
+        }
+    }
+
     pub fn get_default_service(&self) -> Result<&AIServiceConfig> {
         if self.services.is_empty() {
             return Err(anyhow::anyhow!("没有配置任何 AI 服务"));
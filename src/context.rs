@@ -0,0 +1,128 @@
+// 仓库上下文模块
+// 收集分支名、文件列表、重命名/删除状态和子模块变更，作为生成提交信息的补充信息
+
+use crate::git;
+use log::debug;
+use std::process::Command;
+
+/// 为省略说明预留的字符数
+const OMITTED_NOTE_RESERVE: usize = 40;
+
+/// 上下文中的一个段落
+struct Section {
+    title: &'static str,
+    lines: Vec<String>,
+}
+
+/// 构建仓库上下文，amend 为 true 时描述上一次提交，否则描述暂存区；
+/// budget 为字符数上限，为 0 时不生成
+pub fn build_context(amend: bool, budget: usize) -> Option<String> {
+    if budget == 0 {
+        return None;
+    }
+
+    let range: &[&str] = if amend { &["HEAD~1", "HEAD"] } else { &["--cached"] };
+    // 按重要程度排列，超出预算时优先截断靠后的段落
+    let sections = vec![
+        Section { title: "Branch", lines: git::get_current_branch().into_iter().collect() },
+        Section { title: "Added, deleted or renamed files", lines: file_status(range) },
+        Section { title: "Submodule changes", lines: submodule_changes(range) },
+        Section { title: "Diff stat", lines: git_lines(&[&["diff"], range, &["--stat"]].concat()) },
+    ];
+
+    let context = render(&sections, budget);
+    debug!("仓库上下文：\n{}", context);
+    if context.is_empty() {
+        None
+    } else {
+        Some(context)
+    }
+}
+
+fn git_lines(args: &[&str]) -> Vec<String> {
+    match Command::new("git").args(args).output() {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 非普通修改的文件状态（新增、删除、重命名、复制、类型变化）
+fn file_status(range: &[&str]) -> Vec<String> {
+    git_lines(&[&["diff"], range, &["--name-status", "-M"]].concat())
+        .into_iter()
+        .filter(|l| !l.starts_with('M'))
+        .map(|l| l.replace('\t', " "))
+        .collect()
+}
+
+/// 子模块的提交变化，附带新增或回退的提交标题
+fn submodule_changes(range: &[&str]) -> Vec<String> {
+    let submodules: Vec<String> = git_lines(&[&["diff"], range, &["--raw"]].concat())
+        .into_iter()
+        .filter(|l| l.starts_with(":160000") || l.split_whitespace().nth(1) == Some("160000"))
+        .filter_map(|l| l.split('\t').nth(1).map(|p| p.to_string()))
+        .collect();
+
+    submodules.iter()
+        .flat_map(|path| git_lines(&[&["diff"], range, &["--submodule=log", "--", path]].concat()))
+        .collect()
+}
+
+/// 按预算拼接段落，超出部分的行被省略并注明
+fn render(sections: &[Section], budget: usize) -> String {
+    let mut result = String::new();
+    for section in sections.iter().filter(|s| !s.lines.is_empty()) {
+        let header = format!("{}:\n", section.title);
+        if result.len() + header.len() + OMITTED_NOTE_RESERVE > budget {
+            break;
+        }
+        result.push_str(&header);
+
+        for (i, line) in section.lines.iter().enumerate() {
+            // 预留省略说明的空间
+            if result.len() + line.len() + 1 + OMITTED_NOTE_RESERVE > budget {
+                result.push_str(&format!("... ({} more lines omitted)\n", section.lines.len() - i));
+                return result.trim_end().to_string();
+            }
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+    result.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sections() -> Vec<Section> {
+        vec![
+            Section { title: "Branch", lines: vec!["feature/PMS-123".to_string()] },
+            Section { title: "Empty", lines: Vec::new() },
+            Section {
+                title: "Diff stat",
+                lines: (1..=20).map(|i| format!(" src/file{}.rs | 2 +-", i)).collect(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_within_budget() {
+        let context = render(&sections(), 10_000);
+        assert!(context.starts_with("Branch:\nfeature/PMS-123\nDiff stat:\n"));
+        assert!(!context.contains("Empty"));
+        assert!(context.ends_with("src/file20.rs | 2 +-"));
+    }
+
+    #[test]
+    fn test_render_truncates_to_budget() {
+        let context = render(&sections(), 150);
+        assert!(context.len() <= 150);
+        assert!(context.starts_with("Branch:\nfeature/PMS-123\n"));
+        assert!(context.contains("more lines omitted"));
+    }
+}
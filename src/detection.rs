@@ -0,0 +1,207 @@
+// 语言检测模块
+// 按文字系统统计文本中各语言的词数，忽略代码标识符、路径、URL 和提交类型前缀；
+// 对提交信息的标题和正文分别检测，确定哪些部分需要翻译以及翻译方向
+
+use crate::commit::CommitMessage;
+use crate::language::{self, LanguagePair};
+use regex::Regex;
+
+/// 常见的德语虚词，用于区分同样使用拉丁字母的德语和英语
+const GERMAN_WORDS: &[&str] = &[
+    "und", "der", "das", "nicht", "mit", "für", "ist", "ein", "eine", "auf", "den", "dem", "zu", "von", "wird", "werden", "beim", "bei",
+];
+
+fn is_han(c: char) -> bool {
+    ('\u{4E00}'..='\u{9FFF}').contains(&c)      // 基本区
+        || ('\u{3400}'..='\u{4DBF}').contains(&c)   // 扩展 A
+        || ('\u{20000}'..='\u{2EBEF}').contains(&c) // 扩展 B-F
+        || ('\u{F900}'..='\u{FAFF}').contains(&c)   // 兼容汉字
+}
+
+/// 全角标点和 CJK 符号
+fn is_fullwidth_punctuation(c: char) -> bool {
+    ('\u{3000}'..='\u{303F}').contains(&c)
+        || ('\u{FF01}'..='\u{FF0F}').contains(&c)
+        || ('\u{FF1A}'..='\u{FF20}').contains(&c)
+        || ('\u{FF3B}'..='\u{FF40}').contains(&c)
+        || ('\u{FF5B}'..='\u{FF65}').contains(&c)
+}
+
+fn is_kana(c: char) -> bool {
+    ('\u{3040}'..='\u{30FF}').contains(&c) || ('\u{31F0}'..='\u{31FF}').contains(&c)
+}
+
+fn is_hangul(c: char) -> bool {
+    ('\u{AC00}'..='\u{D7AF}').contains(&c) || ('\u{1100}'..='\u{11FF}').contains(&c) || ('\u{3130}'..='\u{318F}').contains(&c)
+}
+
+/// 词与词之间不使用空格的文字（汉字、假名）和全角标点，折行处合并时不需要补空格
+pub fn is_unspaced(c: char) -> bool {
+    is_han(c) || is_kana(c) || is_fullwidth_punctuation(c)
+}
+
+fn is_cyrillic(c: char) -> bool {
+    ('\u{0400}'..='\u{04FF}').contains(&c)
+}
+
+fn is_latin(c: char) -> bool {
+    c.is_ascii_alphabetic() || (('\u{00C0}'..='\u{024F}').contains(&c) && c.is_alphabetic())
+}
+
+/// 去掉不代表自然语言的内容：提交类型前缀、字段名、反引号中的代码、URL、路径和代码标识符
+fn strip_code(text: &str) -> String {
+    let prefix = Regex::new(r"(?m)^\s*(?:[a-z]+(?:\([^)]*\))?!?[:：]|Log:|Influence:)").unwrap();
+    let code = Regex::new(r"`[^`]*`|https?://\S+").unwrap();
+    // 含有 / . _ 数字或驼峰的单词视为路径或标识符
+    let identifier = Regex::new(r"[A-Za-z0-9_./-]*(?:[/._][A-Za-z0-9]|[0-9]|[a-z][A-Z])[A-Za-z0-9_./-]*").unwrap();
+    let text = prefix.replace_all(text, " ");
+    let text = code.replace_all(&text, " ");
+    identifier.replace_all(&text, " ").to_string()
+}
+
+fn looks_german(words: &[String]) -> bool {
+    if words.iter().any(|w| w.chars().any(|c| "äöüßÄÖÜ".contains(c))) {
+        return true;
+    }
+    GERMAN_WORDS.iter().filter(|g| words.iter().any(|w| w.eq_ignore_ascii_case(g))).count() >= 2
+}
+
+/// 统计各语言的词数（汉字、假名、谚文按字计，拉丁字母和西里尔字母按词计），按词数从多到少排列
+pub fn score(text: &str) -> Vec<(&'static str, usize)> {
+    let text = strip_code(text);
+    let (mut han, mut kana, mut hangul, mut punctuation) = (0, 0, 0, 0);
+    for c in text.chars() {
+        if is_han(c) {
+            han += 1;
+        } else if is_kana(c) {
+            kana += 1;
+        } else if is_hangul(c) {
+            hangul += 1;
+        } else if is_fullwidth_punctuation(c) {
+            punctuation += 1;
+        }
+    }
+
+    let words: Vec<String> = text.split(|c: char| !is_latin(c) && !is_cyrillic(c))
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_string())
+        .collect();
+    let (cyrillic, latin): (Vec<String>, Vec<String>) = words.into_iter()
+        .partition(|w| w.chars().any(is_cyrillic));
+
+    let mut scores = Vec::new();
+    // 日文中的汉字与中文无法区分，有假名时全部计入日文；全角标点作为 CJK 语言的辅助依据
+    if kana > 0 {
+        scores.push(("ja", han + kana + punctuation));
+    } else if han > 0 || (punctuation > 0 && hangul == 0) {
+        scores.push(("zh", han + punctuation));
+    }
+    if hangul > 0 {
+        scores.push(("ko", hangul));
+    }
+    if !cyrillic.is_empty() {
+        scores.push(("ru", cyrillic.len()));
+    }
+    if !latin.is_empty() {
+        scores.push((if looks_german(&latin) { "de" } else { "en" }, latin.len()));
+    }
+    scores.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    scores
+}
+
+/// 检测文本的主要语言，没有可识别的自然语言内容时返回 None
+pub fn detect(text: &str) -> Option<&'static str> {
+    score(text).first().map(|(language, _)| *language)
+}
+
+/// 提交信息的翻译计划：译文按默认语言在前、另一种语言在后的顺序排列
+#[derive(Debug, PartialEq)]
+pub struct TranslationPlan {
+    /// 默认语言，排在前面
+    pub primary: String,
+    /// 提交信息中检测到的另一种语言，排在后面
+    pub secondary: String,
+    /// 标题的翻译方向
+    pub title: LanguagePair,
+    /// 正文的翻译方向，没有正文时为 None
+    pub body: Option<LanguagePair>,
+}
+
+/// 分别检测标题和正文的语言，确定需要翻译的部分和方向；
+/// 全部使用默认语言或已经是双语提交信息时返回 None
+pub fn plan(message: &CommitMessage, primary: &str) -> Option<TranslationPlan> {
+    if message.translation.is_some() {
+        return None;
+    }
+    let title_language = detect(&message.title);
+    let body_language = message.body.as_deref().and_then(detect);
+
+    let is_primary = |language: Option<&str>| language.is_none_or(|l| language::same_language(l, primary));
+    let secondary = [title_language, body_language].into_iter()
+        .flatten()
+        .find(|language| !is_primary(Some(language)))?
+        .to_string();
+
+    // 未识别出语言的部分视为与标题（或默认语言）相同
+    let direction = |language: Option<&str>| {
+        if is_primary(language) {
+            LanguagePair::new(primary, &secondary)
+        } else {
+            LanguagePair::new(&secondary, primary)
+        }
+    };
+    Some(TranslationPlan {
+        primary: primary.to_string(),
+        title: direction(title_language),
+        body: message.body.as_ref().map(|_| direction(body_language.or(title_language))),
+        secondary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_mixed_content() {
+        assert_eq!(detect("feat: add login"), Some("en"));
+        assert_eq!(detect("feat: 添加 login 接口"), Some("zh"));
+        assert_eq!(detect("fix: handle 中文 input in the parser"), Some("en"));
+        assert_eq!(detect("fix: 修复 `parse_config_file` 和 src/main.rs 中的 readConfig 崩溃"), Some("zh"));
+        assert_eq!(detect("feat: ログイン機能を追加"), Some("ja"));
+        assert_eq!(detect("feat: 로그인 추가"), Some("ko"));
+        assert_eq!(detect("fix: Fehler beim Speichern der Datei behoben"), Some("de"));
+        assert_eq!(detect("fix: Anmeldung für Benutzer"), Some("de"));
+        // 扩展 A 汉字和全角标点
+        assert_eq!(detect("fix: 㐀㐁"), Some("zh"));
+        assert_eq!(detect("docs：（）"), Some("zh"));
+        assert_eq!(detect("chore: bump v1.2.3"), Some("en"));
+        assert_eq!(score("Fix the crash."), vec![("en", 3)]);
+        assert_eq!(detect("`foo_bar` 1.0"), None);
+    }
+
+    #[test]
+    fn test_plan_title_and_body() {
+        let message = CommitMessage::parse("fix: handle empty input\n\n修复输入为空时解析器崩溃的问题");
+        assert_eq!(plan(&message, "en"), Some(TranslationPlan {
+            primary: "en".to_string(),
+            secondary: "zh".to_string(),
+            title: LanguagePair::new("en", "zh"),
+            body: Some(LanguagePair::new("zh", "en")),
+        }));
+
+        let message = CommitMessage::parse("feat: 添加登录");
+        let result = plan(&message, "en").unwrap();
+        assert_eq!(result.title, LanguagePair::new("zh", "en"));
+        assert_eq!(result.body, None);
+
+        let message = CommitMessage::parse("feat: ログイン機能を追加\n\n1. Add `login()` API");
+        let result = plan(&message, "en").unwrap();
+        assert_eq!(result.secondary, "ja");
+        assert_eq!(result.body, Some(LanguagePair::new("en", "ja")));
+
+        assert_eq!(plan(&CommitMessage::parse("feat: add login\n\n1. Add endpoint"), "en"), None);
+        assert_eq!(plan(&CommitMessage::parse("feat: 添加登录\n\n1. 添加接口"), "zh"), None);
+        assert_eq!(plan(&CommitMessage::parse("feat: add login\n\n1. Add endpoint\n\nfeat: 添加登录\n\n1. 添加接口"), "en"), None);
+    }
+}
@@ -0,0 +1,191 @@
+// diff 解析模块
+// 将 git diff 输出拆分为文件和 hunk，并支持按 hunk 重新组装补丁
+
+use regex::Regex;
+
+/// 单个 hunk（以 @@ 开头的变更块）
+#[derive(Debug, Clone)]
+pub struct Hunk {
+    pub header: String,
+    pub lines: Vec<String>,
+    /// 原文件中的起始行和行数，行数为 0 时表示在起始行之后插入
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+}
+
+impl Hunk {
+    pub fn text(&self) -> String {
+        let mut text = self.header.clone();
+        for line in &self.lines {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text
+    }
+}
+
+/// 出现这些头部时，补丁只能整体应用一次：拆开后后续补丁会因旧路径已不存在或权限已变更而失败
+const ATOMIC_HEADERS: [&str; 6] = [
+    "new file mode",
+    "deleted file mode",
+    "rename from",
+    "copy from",
+    "old mode",
+    "similarity index",
+];
+
+/// 单个文件的 diff
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub path: String,
+    pub header: Vec<String>,
+    pub hunks: Vec<Hunk>,
+    /// 二进制补丁、纯重命名或权限变更等无法按 hunk 拆分的内容
+    pub extra: Vec<String>,
+}
+
+impl FileDiff {
+    /// 文件是否只能作为一个整体处理
+    pub fn is_atomic(&self) -> bool {
+        self.hunks.is_empty()
+            || !self.extra.is_empty()
+            || self.header.iter().any(|l| ATOMIC_HEADERS.iter().any(|prefix| l.starts_with(prefix)))
+    }
+
+    /// 仅包含指定 hunk 的补丁内容，indices 为 hunks 中的下标
+    pub fn patch_for(&self, indices: &[usize]) -> String {
+        let mut lines = self.header.clone();
+        lines.extend(self.extra.iter().cloned());
+        for (i, hunk) in self.hunks.iter().enumerate() {
+            if self.is_atomic() || indices.contains(&i) {
+                lines.push(hunk.text());
+            }
+        }
+        let mut patch = lines.join("\n");
+        patch.push('\n');
+        patch
+    }
+}
+
+/// 解析 git diff 的输出
+pub fn parse_diff(diff: &str) -> Vec<FileDiff> {
+    let hunk_regex = Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,\d+)? @@").unwrap();
+    let path_regex = Regex::new(r"^diff --git (?:a/)?(\S+) (?:b/)?(\S+)").unwrap();
+
+    let mut files: Vec<FileDiff> = Vec::new();
+    // 不使用 lines()，以免丢失 CRLF 文件中行尾的 \r
+    for line in diff.strip_suffix('\n').unwrap_or(diff).split('\n') {
+        if line.starts_with("diff --git ") {
+            let path = path_regex.captures(line)
+                .map(|c| c[2].to_string())
+                .unwrap_or_default();
+            files.push(FileDiff {
+                path,
+                header: vec![line.to_string()],
+                hunks: Vec::new(),
+                extra: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if let Some(captures) = hunk_regex.captures(line) {
+            file.hunks.push(Hunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+                old_start: captures[1].parse().unwrap_or(0),
+                old_lines: captures.get(2).map_or(1, |m| m.as_str().parse().unwrap_or(0)),
+                new_start: captures[3].parse().unwrap_or(0),
+            });
+        } else if let Some(hunk) = file.hunks.last_mut() {
+            hunk.lines.push(line.to_string());
+        } else if !file.extra.is_empty() || line.starts_with("GIT binary patch") || line.starts_with("Binary files ") {
+            file.extra.push(line.to_string());
+        } else {
+            file.header.push(line.to_string());
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/a.rs b/src/a.rs
+index 1111111..2222222 100644
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1,3 +1,3 @@
+ fn a() {}
+-fn b() {}
++fn b() { todo!() }
+ fn c() {}
+@@ -10,2 +10,3 @@ fn d() {}
+ fn e() {}
++fn f() {}
+ fn g() {}
+diff --git a/new.txt b/new.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++hello
+";
+
+    #[test]
+    fn test_parse_diff_files_and_hunks() {
+        let files = parse_diff(DIFF);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/a.rs");
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(files[0].hunks[1].new_start, 10);
+        assert_eq!((files[0].hunks[1].old_start, files[0].hunks[1].old_lines), (10, 2));
+        assert_eq!((files[1].hunks[0].old_start, files[1].hunks[0].old_lines), (0, 0));
+        assert_eq!(files[0].hunks[1].lines.len(), 3);
+        assert!(!files[0].is_atomic());
+        assert!(files[1].is_atomic());
+    }
+
+    #[test]
+    fn test_patch_for_selected_hunk() {
+        let files = parse_diff(DIFF);
+        let patch = files[0].patch_for(&[1]);
+        assert!(patch.starts_with("diff --git a/src/a.rs b/src/a.rs\n"));
+        assert!(patch.contains("+++ b/src/a.rs\n@@ -10,2 +10,3 @@"));
+        assert!(!patch.contains("todo!()"));
+        assert!(patch.ends_with(" fn g() {}\n"));
+    }
+
+    #[test]
+    fn test_renamed_file_is_atomic() {
+        let diff = "diff --git a/old.rs b/new.rs
+similarity index 90%
+rename from old.rs
+rename to new.rs
+index 1111111..2222222 100644
+--- a/old.rs
++++ b/new.rs
+@@ -1,2 +1,2 @@
+-fn a() {}
++fn a() { todo!() }
+ fn b() {}
+@@ -20,2 +20,2 @@ fn c() {}
+-fn d() {}
++fn d() { todo!() }
+ fn e() {}
+";
+        let files = parse_diff(diff);
+        assert_eq!(files[0].hunks.len(), 2);
+        assert!(files[0].is_atomic());
+        let patch = files[0].patch_for(&[1]);
+        assert_eq!(patch.matches("rename from old.rs").count(), 1);
+        assert!(patch.contains("@@ -1,2 +1,2 @@"));
+        assert!(patch.contains("@@ -20,2 +20,2 @@"));
+    }
+}
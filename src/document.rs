@@ -0,0 +1,640 @@
+// 文档翻译模块
+// 按格式提取 Markdown、gettext .po 和 Qt .ts 文件中需要翻译的片段，分批翻译后按原有结构写回：
+// 代码块、链接定义、HTML、注释和已有译文保持不变，.po 和 .ts 中新增的译文标记为待审核
+
+use crate::ai_service::TextKind;
+use crate::batch;
+use crate::config::Config;
+use crate::detection;
+use crate::language::{self, LanguagePair};
+use crate::terminal_format::Style;
+use clap::ValueEnum;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum DocumentFormat {
+    /// Markdown 文档
+    Markdown,
+    /// gettext 翻译文件
+    Po,
+    /// Qt Linguist 翻译文件
+    Ts,
+}
+
+pub struct DocumentOptions {
+    pub input: PathBuf,
+    pub content: String,
+    pub format: DocumentFormat,
+    pub output: Option<PathBuf>,  // 输出文件，未指定时为输入文件同目录下的 <文件名>.<目标语言>.<扩展名>
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// 逐个片段调用的翻译函数，返回 None 时保留原文（.po 和 .ts 中保留空译文）
+type Translate<'a> = &'a mut dyn FnMut(&str) -> Option<String>;
+
+impl DocumentFormat {
+    /// 根据扩展名判断格式，.ts 文件需要是 Qt Linguist 的 XML 格式
+    pub fn detect(path: &Path, content: &str) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "po" | "pot" => Some(Self::Po),
+            "ts" if content.contains("<TS") => Some(Self::Ts),
+            _ => None,
+        }
+    }
+
+    fn render(&self, content: &str, translate: Translate) -> String {
+        match self {
+            Self::Markdown => markdown(content, translate),
+            Self::Po => po(content, translate),
+            Self::Ts => ts(content, translate),
+        }
+    }
+
+    /// 文件中声明的源语言和目标语言
+    fn languages(&self, content: &str) -> (Option<String>, Option<String>) {
+        let capture = |pattern: &str| {
+            Regex::new(pattern).unwrap()
+                .captures(content)
+                .and_then(|c| language::normalize(&c[1]).ok())
+        };
+        match self {
+            Self::Markdown => (None, None),
+            Self::Po => (None, capture(r#"(?m)^"Language: ([A-Za-z_-]+)\\n"$"#)),
+            Self::Ts => (
+                capture(r#"<TS\b[^>]*\ssourcelanguage="([^"]+)""#),
+                capture(r#"<TS\b[^>]*\slanguage="([^"]+)""#),
+            ),
+        }
+    }
+}
+
+/// 翻译片段并保留两端的空白，不含文字的片段保持原样
+fn translate_segment(text: &str, translate: Translate) -> String {
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len();
+    if start >= end || !text[start..end].chars().any(char::is_alphabetic) {
+        return text.to_string();
+    }
+    match translate(&text[start..end]) {
+        Some(translated) => format!("{}{}{}", &text[..start], translated, &text[end..]),
+        None => text.to_string(),
+    }
+}
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).unwrap())
+}
+
+/// Markdown 中的一行
+enum MarkdownLine<'a> {
+    /// 保持原样：front matter、代码块、注释、空行、分隔线、HTML 和链接定义
+    Keep(&'a str),
+    /// 表格行，逐个单元格翻译
+    Table(&'a str),
+    /// 文本行：行首标记（缩进、引用、标题或列表标记）和正文
+    Text { marker: &'a str, text: &'a str },
+}
+
+impl MarkdownLine<'_> {
+    /// 行首标记中的标题或列表标记，没有时为空
+    fn block_marker(&self) -> &str {
+        match self {
+            Self::Text { marker, .. } => marker.trim_start_matches(|c: char| c.is_whitespace() || c == '>'),
+            _ => "",
+        }
+    }
+}
+
+/// 拆出行尾的硬换行（两个以上空格或反斜杠）和其他尾随空白
+fn split_line_break(text: &str) -> (&str, &str) {
+    let content = text.trim_end();
+    let content = content.strip_suffix('\\').unwrap_or(content);
+    text.split_at(content.len())
+}
+
+fn has_hard_break(text: &str) -> bool {
+    let (_, line_break) = split_line_break(text);
+    line_break.starts_with('\\') || line_break.starts_with("  ")
+}
+
+/// 将段落中软折行的多行合并为一个片段，中文和日文之间不补空格
+fn join_soft_wrapped(texts: &[&str]) -> String {
+    let mut joined = String::new();
+    for text in texts {
+        let text = text.trim();
+        let unspaced = joined.chars().last().is_some_and(detection::is_unspaced)
+            || text.chars().next().is_some_and(detection::is_unspaced);
+        if !joined.is_empty() && !unspaced {
+            joined.push(' ');
+        }
+        joined.push_str(text);
+    }
+    joined
+}
+
+/// 翻译 Markdown：按段落翻译标题、段落、列表项、引用和表格单元格，行首标记保持不变；
+/// 段落中软折行的多行合并为一个片段，译文写为一行，硬换行处分为多个片段
+fn markdown(content: &str, translate: Translate) -> String {
+    let lines = markdown_lines(content);
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let marker = match &lines[i] {
+            MarkdownLine::Keep(line) => {
+                result.push(line.to_string());
+                i += 1;
+                continue;
+            }
+            MarkdownLine::Table(line) => {
+                // 表格逐个单元格翻译，分隔行不含文字保持原样
+                result.push(line.split('|').map(|cell| translate_segment(cell, translate)).collect::<Vec<_>>().join("|"));
+                i += 1;
+                continue;
+            }
+            MarkdownLine::Text { marker, .. } => *marker,
+        };
+
+        // 标题只占一行；其他段落延续到空行、结构行、新的标题或列表项，或在硬换行处结束
+        let mut end = i + 1;
+        if !lines[i].block_marker().starts_with('#') {
+            while let (Some(MarkdownLine::Text { text: previous, .. }), Some(next)) = (lines.get(end - 1), lines.get(end)) {
+                if has_hard_break(previous) || !matches!(next, MarkdownLine::Text { .. }) || !next.block_marker().is_empty() {
+                    break;
+                }
+                end += 1;
+            }
+        }
+        let texts: Vec<&str> = lines[i..end].iter()
+            .filter_map(|line| match line {
+                MarkdownLine::Text { text, .. } => Some(*text),
+                _ => None,
+            })
+            .collect();
+
+        let (last, line_break) = split_line_break(texts[texts.len() - 1]);
+        let segment = match texts.len() {
+            1 => last.to_string(),
+            n => join_soft_wrapped(&[&texts[..n - 1], &[last]].concat()),
+        };
+        let translated = translate_segment(&segment, translate);
+        if translated == segment {
+            // 未翻译时保持原有的折行
+            result.extend(lines[i..end].iter().filter_map(|line| match line {
+                MarkdownLine::Text { marker, text } => Some(format!("{}{}", marker, text)),
+                _ => None,
+            }));
+        } else {
+            result.push(format!("{}{}{}", marker, translated, line_break));
+        }
+        i = end;
+    }
+    let mut result = result.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// 将 Markdown 按行分类，结构行保持原样
+fn markdown_lines(content: &str) -> Vec<MarkdownLine<'_>> {
+    static PREFIX: OnceLock<Regex> = OnceLock::new();
+    static LIST_ITEM: OnceLock<Regex> = OnceLock::new();
+    static LINK_DEFINITION: OnceLock<Regex> = OnceLock::new();
+    static THEMATIC_BREAK: OnceLock<Regex> = OnceLock::new();
+    let prefix = regex(&PREFIX, r"^\s*(?:>\s*)*(?:#{1,6}\s+|[-*+]\s+(?:\[[ xX]\]\s+)?|\d{1,9}[.)]\s+(?:\[[ xX]\]\s+)?)?");
+    let list_item = regex(&LIST_ITEM, r"^\s*(?:[-*+]|\d{1,9}[.)])\s+");
+    let link_definition = regex(&LINK_DEFINITION, r"^\s*\[[^\]]+\]:\s");
+    // 分隔线和 setext 标题的下划线
+    let thematic_break = regex(&THEMATIC_BREAK, r"^\s*(?:[-*_=]\s*){3,}$");
+
+    let mut lines = Vec::new();
+    let mut front_matter = false;
+    let mut fence: Option<&str> = None;
+    let mut in_comment = false;
+    let mut in_list = false;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        // 列表项之后缩进的行属于列表，顶格的行结束列表
+        if !trimmed.is_empty() && !front_matter && fence.is_none() && !in_comment {
+            if list_item.is_match(line) {
+                in_list = true;
+            } else if !line.starts_with(char::is_whitespace) {
+                in_list = false;
+            }
+        }
+        let keep = if i == 0 && line.trim_end() == "---" {
+            front_matter = true;
+            true
+        } else if front_matter {
+            front_matter = !matches!(line.trim_end(), "---" | "...");
+            true
+        } else if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            true
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            true
+        } else if in_comment || trimmed.starts_with("<!--") {
+            in_comment = !line.contains("-->");
+            true
+        } else if trimmed.is_empty() || thematic_break.is_match(line) {
+            true
+        } else {
+            let indented = line.starts_with('\t') || line.starts_with("    ");
+            // 列表外缩进的行为代码块
+            trimmed.starts_with('<') || link_definition.is_match(line) || (indented && !in_list)
+        };
+        if keep {
+            lines.push(MarkdownLine::Keep(line));
+        } else if trimmed.starts_with('|') {
+            lines.push(MarkdownLine::Table(line));
+        } else {
+            let marker = prefix.find(line).map(|m| m.end()).unwrap_or(0);
+            lines.push(MarkdownLine::Text { marker: &line[..marker], text: &line[marker..] });
+        }
+    }
+    lines
+}
+
+fn po_unescape(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+fn po_escape(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+/// .po 字段，多行内容按 gettext 的习惯从下一行开始逐行书写
+fn po_field(keyword: &str, value: &str) -> Vec<String> {
+    if !value.trim_end_matches('\n').contains('\n') {
+        return vec![format!("{} \"{}\"", keyword, po_escape(value))];
+    }
+    std::iter::once(format!("{} \"\"", keyword))
+        .chain(value.split_inclusive('\n').map(|line| format!("\"{}\"", po_escape(line))))
+        .collect()
+}
+
+/// .po 中的一个字段：关键字、原始行和转义前的内容
+struct PoField<'a> {
+    keyword: &'a str,
+    lines: Vec<&'a str>,
+    value: String,
+}
+
+/// 翻译 .po 中的一个条目：只填写 msgstr 为空的条目，并加上 fuzzy 标记等待审核
+fn po_entry<'a>(lines: &[&'a str], translate: Translate) -> Vec<String> {
+    static FIELD: OnceLock<Regex> = OnceLock::new();
+    let field = regex(&FIELD, r#"^(msgctxt|msgid|msgid_plural|msgstr(?:\[\d+\])?)\s+"(.*)"\s*$"#);
+    let unchanged = || lines.iter().map(|line| line.to_string()).collect::<Vec<_>>();
+
+    let mut comments: Vec<&str> = Vec::new();
+    let mut fields: Vec<PoField<'a>> = Vec::new();
+    for &line in lines {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') && fields.is_empty() {
+            comments.push(line);
+        } else if let Some(captures) = field.captures(trimmed) {
+            fields.push(PoField {
+                keyword: captures.get(1).unwrap().as_str(),
+                lines: vec![line],
+                value: po_unescape(&captures[2]),
+            });
+        } else if let (Some(last), Some(quoted)) = (fields.last_mut(), trimmed.strip_prefix('"').and_then(|s| s.strip_suffix('"'))) {
+            last.lines.push(line);
+            last.value.push_str(&po_unescape(quoted));
+        } else {
+            return unchanged();
+        }
+    }
+
+    let value = |keyword: &str| fields.iter().find(|f| f.keyword == keyword).map(|f| f.value.as_str());
+    let msgid = value("msgid").unwrap_or_default();
+    let translated = fields.iter().any(|f| f.keyword.starts_with("msgstr") && !f.value.is_empty());
+    // 文件头（msgid 为空）和已有译文的条目保持不变
+    if msgid.is_empty() || translated {
+        return unchanged();
+    }
+    let Some(singular) = translate(msgid) else {
+        return unchanged();
+    };
+    let plural = match value("msgid_plural") {
+        Some(text) => translate(text).unwrap_or_else(|| singular.clone()),
+        None => singular.clone(),
+    };
+
+    let mut result: Vec<String> = Vec::new();
+    let mut flagged = false;
+    for comment in &comments {
+        if let Some(flags) = comment.strip_prefix("#,") {
+            flagged = true;
+            if flags.split(',').any(|flag| flag.trim() == "fuzzy") {
+                result.push(comment.to_string());
+            } else {
+                result.push(format!("#, fuzzy,{}", flags));
+            }
+        } else {
+            // fuzzy 标记放在 #| 之前
+            if !flagged && comment.starts_with("#|") {
+                result.push("#, fuzzy".to_string());
+                flagged = true;
+            }
+            result.push(comment.to_string());
+        }
+    }
+    if !flagged {
+        result.push("#, fuzzy".to_string());
+    }
+    for field in &fields {
+        match field.keyword {
+            "msgstr" | "msgstr[0]" => result.extend(po_field(field.keyword, &singular)),
+            keyword if keyword.starts_with("msgstr") => result.extend(po_field(keyword, &plural)),
+            _ => result.extend(field.lines.iter().map(|line| line.to_string())),
+        }
+    }
+    result
+}
+
+/// 翻译 .po：按空行拆分条目逐个处理
+fn po(content: &str, translate: Translate) -> String {
+    let mut result: Vec<String> = Vec::new();
+    let mut entry: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            result.extend(po_entry(&entry, translate));
+            entry.clear();
+            result.push(line.to_string());
+        } else {
+            entry.push(line);
+        }
+    }
+    result.extend(po_entry(&entry, translate));
+    let mut output = result.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    output
+}
+
+fn xml_unescape(value: &str) -> String {
+    value.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn xml_escape(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// 翻译 Qt .ts 中的一条消息：只填写空的译文，已废弃的消息保持不变
+fn ts_message(message: &str, translate: Translate) -> String {
+    static SOURCE: OnceLock<Regex> = OnceLock::new();
+    static TRANSLATION: OnceLock<Regex> = OnceLock::new();
+    static NUMERUS_FORM: OnceLock<Regex> = OnceLock::new();
+    let source = regex(&SOURCE, r"(?s)<source>(.*?)</source>");
+    let translation = regex(&TRANSLATION, r"(?s)<translation\b([^>]*?)(?:/>|>(.*?)</translation>)");
+    let numerus_form = regex(&NUMERUS_FORM, r"(?s)<numerusform\b[^>]*?(?:/>|>(.*?)</numerusform>)");
+
+    let (Some(text), Some(existing)) = (source.captures(message), translation.captures(message)) else {
+        return message.to_string();
+    };
+    let attributes = existing.get(1).map(|m| m.as_str()).unwrap_or_default();
+    let inner = existing.get(2).map(|m| m.as_str()).unwrap_or_default();
+    if attributes.contains("vanished") || attributes.contains("obsolete") {
+        return message.to_string();
+    }
+
+    let numerus = message[..message.find('>').unwrap_or(0)].contains("numerus=\"yes\"");
+    let forms: Vec<_> = numerus_form.captures_iter(inner).collect();
+    let empty = if numerus {
+        forms.iter().all(|form| form.get(1).is_none_or(|m| m.as_str().trim().is_empty()))
+    } else {
+        inner.trim().is_empty()
+    };
+    if !empty {
+        return message.to_string();
+    }
+    let Some(translated) = translate(&xml_unescape(&text[1])) else {
+        return message.to_string();
+    };
+
+    // 机器翻译的结果标记为 unfinished，在 Qt Linguist 中审核后再发布
+    let attributes = if attributes.contains("type=") {
+        attributes.to_string()
+    } else {
+        format!("{} type=\"unfinished\"", attributes)
+    };
+    let content = if numerus {
+        format!("<numerusform>{}</numerusform>", xml_escape(&translated)).repeat(forms.len().max(1))
+    } else {
+        xml_escape(&translated)
+    };
+    let range = existing.get(0).unwrap().range();
+    format!(
+        "{}<translation{}>{}</translation>{}",
+        &message[..range.start],
+        attributes,
+        content,
+        &message[range.end..]
+    )
+}
+
+/// 翻译 Qt .ts：逐条处理 <message>
+fn ts(content: &str, translate: Translate) -> String {
+    static MESSAGE: OnceLock<Regex> = OnceLock::new();
+    let message = regex(&MESSAGE, r"(?s)<message\b[^>]*>.*?</message>");
+    message.replace_all(content, |captures: &Captures| ts_message(&captures[0], translate)).into_owned()
+}
+
+/// 默认输出文件：输入文件同目录下的 <文件名>.<目标语言>.<扩展名>
+fn default_output(input: &Path, target: &str) -> PathBuf {
+    let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+    let name = match input.extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}.{}.{}", stem, target, extension),
+        None => format!("{}.{}", stem, target),
+    };
+    input.with_file_name(name)
+}
+
+/// 按格式翻译文件并写入输出文件
+pub async fn run(options: DocumentOptions) -> anyhow::Result<()> {
+    let config = Config::load()?;
+    let format = options.format;
+
+    // 第一遍只收集需要翻译的片段
+    let mut segments: Vec<String> = Vec::new();
+    format.render(&options.content, &mut |text| {
+        if !segments.iter().any(|s| s == text) {
+            segments.push(text.to_string());
+        }
+        None
+    });
+    if segments.is_empty() {
+        println!("{}", Style::yellow("没有需要翻译的内容"));
+        return Ok(());
+    }
+
+    // 翻译方向：命令行参数优先，其次为文件中声明的语言，最后自动检测或使用配置文件中的默认方向
+    let (declared_source, declared_target) = format.languages(&options.content);
+    let target = match options.to {
+        Some(to) => language::normalize(&to)?,
+        None => declared_target.unwrap_or_else(|| config.translate_direction.target.clone()),
+    };
+    let source = match options.from {
+        Some(from) => language::normalize(&from)?,
+        None => declared_source
+            .or_else(|| detection::detect(&segments.join("\n")).map(|s| s.to_string()))
+            .unwrap_or_else(|| config.translate_direction.source.clone()),
+    };
+    if language::same_language(&source, &target) {
+        return Err(anyhow::anyhow!("源语言和目标语言相同（{}），无需翻译", language::display_name(&source)));
+    }
+    let direction = LanguagePair::new(&source, &target);
+    eprintln!("{} 中共有 {} 个片段需要翻译（{}）", options.input.display(), segments.len(), direction);
+
+    let texts: Vec<&str> = segments.iter().map(|s| s.as_str()).collect();
+    let results = batch::translate_all(&config, &texts, &direction, TextKind::Document).await?;
+    let translations: HashMap<&str, String> = texts.iter()
+        .zip(results)
+        .map(|(source, translated)| (*source, translated.trim().to_string()))
+        .filter(|(_, translated)| !translated.is_empty())
+        .collect();
+
+    let translated = format.render(&options.content, &mut |text| translations.get(text).cloned());
+    let output = options.output.unwrap_or_else(|| default_output(&options.input, &target));
+    std::fs::write(&output, translated)?;
+    print!("{}", Style::green(&format!("已将 {} 个片段的译文写入 {}", translations.len(), output.display())));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用的翻译：在原文前加上 T:
+    fn mark(text: &str) -> Option<String> {
+        Some(format!("T:{}", text))
+    }
+
+    #[test]
+    fn test_markdown() {
+        let content = "---\ntitle: 文档\n---\n# 安装\n\n1. 下载 [安装包](https://example.com)  \n   继续说明\n\n```bash\ncargo install\n```\n\n    indented code\n\n| 名称 | 说明 |\n|------|------|\n| a | 第一项 |\n\n<!-- 注释\n-->\n[link]: https://example.com\n";
+        assert_eq!(
+            markdown(content, &mut mark),
+            "---\ntitle: 文档\n---\n# T:安装\n\n1. T:下载 [安装包](https://example.com)  \n   T:继续说明\n\n```bash\ncargo install\n```\n\n    indented code\n\n| T:名称 | T:说明 |\n|------|------|\n| T:a | T:第一项 |\n\n<!-- 注释\n-->\n[link]: https://example.com\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_paragraphs() {
+        // 软折行的段落合并为一个片段，中文之间不补空格，英文之间补空格
+        let content = "# 标题\n这是第一行，\n这是第二行。\n\n> Quoted text that\n> wraps here\\\n> after break\n\n- item one\n  continues\n- item two\n\nTitle\n=====\n";
+        let mut segments = Vec::new();
+        let translated = markdown(content, &mut |text| {
+            segments.push(text.to_string());
+            Some(format!("T:{}", text))
+        });
+        assert_eq!(segments, vec![
+            "标题",
+            "这是第一行，这是第二行。",
+            "Quoted text that wraps here",
+            "after break",
+            "item one continues",
+            "item two",
+            "Title",
+        ]);
+        assert_eq!(
+            translated,
+            "# T:标题\nT:这是第一行，这是第二行。\n\n> T:Quoted text that wraps here\\\n> T:after break\n\n- T:item one continues\n- T:item two\n\nT:Title\n=====\n"
+        );
+
+        // 未翻译的段落保持原有折行
+        assert_eq!(markdown("第一行\n第二行\n", &mut |_| None), "第一行\n第二行\n");
+    }
+
+    #[test]
+    fn test_po() {
+        let content = concat!(
+            "msgid \"\"\nmsgstr \"\"\n\"Language: zh_CN\\n\"\n\n",
+            "#: main.cpp:10\nmsgid \"Open \\\"file\\\"\"\nmsgstr \"\"\n\n",
+            "#, c-format\nmsgid \"%d file\"\nmsgid_plural \"%d files\"\nmsgstr[0] \"\"\nmsgstr[1] \"\"\n\n",
+            "msgid \"Done\"\nmsgstr \"完成\"\n",
+        );
+        assert_eq!(
+            po(content, &mut mark),
+            concat!(
+                "msgid \"\"\nmsgstr \"\"\n\"Language: zh_CN\\n\"\n\n",
+                "#: main.cpp:10\n#, fuzzy\nmsgid \"Open \\\"file\\\"\"\nmsgstr \"T:Open \\\"file\\\"\"\n\n",
+                "#, fuzzy, c-format\nmsgid \"%d file\"\nmsgid_plural \"%d files\"\nmsgstr[0] \"T:%d file\"\nmsgstr[1] \"T:%d files\"\n\n",
+                "msgid \"Done\"\nmsgstr \"完成\"\n",
+            )
+        );
+        assert_eq!(DocumentFormat::Po.languages(content), (None, Some("zh-CN".to_string())));
+        assert_eq!(po_field("msgstr", "a\nb"), vec!["msgstr \"\"", "\"a\\n\"", "\"b\""]);
+    }
+
+    #[test]
+    fn test_ts() {
+        let content = r#"<TS version="2.1" language="zh_CN" sourcelanguage="en">
+<context>
+    <message>
+        <source>Open &amp; save</source>
+        <translation type="unfinished"></translation>
+    </message>
+    <message>
+        <source>Close</source>
+        <translation>关闭</translation>
+    </message>
+    <message numerus="yes">
+        <source>%n file(s)</source>
+        <translation type="unfinished">
+            <numerusform></numerusform>
+        </translation>
+    </message>
+    <message>
+        <source>Old</source>
+        <translation type="vanished"></translation>
+    </message>
+</context>
+</TS>"#;
+        let translated = ts(content, &mut mark);
+        assert!(translated.contains(r#"<translation type="unfinished">T:Open &amp; save</translation>"#));
+        assert!(translated.contains("<translation>关闭</translation>"));
+        assert!(translated.contains(r#"<translation type="unfinished"><numerusform>T:%n file(s)</numerusform></translation>"#));
+        assert!(translated.contains(r#"<translation type="vanished"></translation>"#));
+        assert_eq!(
+            DocumentFormat::Ts.languages(content),
+            (Some("en".to_string()), Some("zh-CN".to_string()))
+        );
+    }
+}
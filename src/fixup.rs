@@ -0,0 +1,257 @@
+// fixup 模块
+// 判断暂存的改动是否是对最近某个未推送提交的修正：对改动涉及的行执行 git blame，
+// 统计各提交命中的行数，再由 AI 确认，建议使用 git commit --fixup 代替生成新的提交信息
+
+use crate::ai_service;
+use crate::config;
+use crate::diff::{self, Hunk};
+use crate::prompt;
+use log::debug;
+use regex::Regex;
+use std::collections::HashMap;
+use std::process::Command;
+
+const FIXUP_PROMPT: &str = r#"You are reviewing a staged git change. Decide whether it is a small follow-up fix to the given earlier commit (for example fixing a bug, a typo or a review comment in the code that commit introduced) rather than an independent change.
+Respond with ONLY "YES" or "NO" on the first line, followed by a one-sentence reason on the second line."#;
+
+/// 提交 diff 发送给 AI 时的最大字符数
+const MAX_COMMIT_DIFF_CHARS: usize = 8000;
+
+pub struct FixupOptions {
+    pub depth: usize,  // 检查最近多少个未推送的提交
+    pub autosquash: bool,  // 创建 fixup 提交后执行 git rebase --autosquash
+    pub no_ai: bool,  // 只根据 blame 结果判断
+}
+
+/// 可能的 fixup 目标
+#[derive(Debug)]
+struct Candidate {
+    sha: String,
+    title: String,
+    lines: usize,  // 暂存改动涉及的行中属于该提交的行数
+}
+
+fn git(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "执行 git {} 失败: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 最近的未推送提交，不包含合并提交和已有的 fixup!/squash!/amend! 提交
+fn recent_commits(depth: usize) -> anyhow::Result<Vec<(String, String)>> {
+    let log = git(&["log", "--no-merges", "--format=%H%x1f%s", &format!("-n{}", depth), "HEAD", "--not", "--remotes"])?;
+    Ok(log.lines()
+        .filter_map(|line| line.split_once('\x1f'))
+        .filter(|(_, title)| !["fixup!", "squash!", "amend!"].iter().any(|p| title.starts_with(p)))
+        .map(|(sha, title)| (sha.to_string(), title.to_string()))
+        .collect())
+}
+
+/// hunk 在原文件中需要 blame 的行范围（起始行，行数）；
+/// 纯新增的 hunk 取插入位置前后的行
+fn blame_range(hunk: &Hunk) -> (usize, usize) {
+    if hunk.old_lines > 0 {
+        (hunk.old_start, hunk.old_lines)
+    } else if hunk.old_start == 0 {
+        (1, 1)
+    } else {
+        (hunk.old_start, 2)
+    }
+}
+
+/// 从 git blame --porcelain 的输出中取出每一行所属的提交
+fn parse_blame(output: &str) -> Vec<String> {
+    let regex = Regex::new(r"^([0-9a-f]{40}) \d+ \d+").unwrap();
+    output.lines()
+        .filter_map(|line| regex.captures(line))
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+fn blame(path: &str, (start, count): (usize, usize)) -> Vec<String> {
+    let range = format!("{},+{}", start, count);
+    match git(&["blame", "--porcelain", "-L", &range, "HEAD", "--", path]) {
+        Ok(output) => parse_blame(&output),
+        // 插入到文件末尾时第二行不存在，只检查前一行
+        Err(_) if count == 2 => blame(path, (start, 1)),
+        Err(e) => {
+            debug!("blame {} {} 失败: {}", path, range, e);
+            Vec::new()
+        }
+    }
+}
+
+/// 统计暂存改动涉及的行属于哪些最近提交，按命中行数从多到少排列，同时返回涉及的总行数
+fn rank_candidates(commits: &[(String, String)]) -> anyhow::Result<(Vec<Candidate>, usize)> {
+    let staged = git(&["diff", "--cached", "-U0", "--no-renames"])?;
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut total = 0;
+    for file in diff::parse_diff(&staged) {
+        // 新文件没有历史可以 blame
+        if file.header.iter().any(|l| l.starts_with("new file mode")) {
+            continue;
+        }
+        for hunk in &file.hunks {
+            for sha in blame(&file.path, blame_range(hunk)) {
+                total += 1;
+                *counts.entry(sha).or_default() += 1;
+            }
+        }
+    }
+
+    let mut candidates: Vec<Candidate> = commits.iter()
+        .filter_map(|(sha, title)| {
+            let lines = *counts.get(sha)?;
+            Some(Candidate { sha: sha.clone(), title: title.clone(), lines })
+        })
+        .collect();
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.lines));
+    Ok((candidates, total))
+}
+
+/// AI 的回答是否确认为 fixup
+fn is_confirmed(response: &str) -> bool {
+    response.trim_start().to_uppercase().starts_with("YES")
+}
+
+/// 请求 AI 确认暂存改动是否是对目标提交的修正，返回是否确认和理由
+async fn confirm_with_ai(candidate: &Candidate) -> anyhow::Result<(bool, String)> {
+    let config = config::Config::load()?;
+    let translator = ai_service::create_translator(&config).await?;
+
+    let message = git(&["log", "-1", "--format=%B", &candidate.sha])?;
+    let commit_diff: String = git(&["show", "--format=", &candidate.sha])?
+        .chars()
+        .take(MAX_COMMIT_DIFF_CHARS)
+        .collect();
+    let staged = git(&["diff", "--cached"])?;
+    let content = format!(
+        "Earlier commit message:\n{}\n\nEarlier commit diff:\n{}\n\nStaged diff:\n{}",
+        message.trim(), commit_diff, staged
+    );
+
+    let response = translator.chat(FIXUP_PROMPT, &content).await?;
+    eprintln!();
+    debug!("AI 返回的判断结果：\n{}", response);
+    let reason = response.lines().skip(1).collect::<Vec<_>>().join(" ").trim().to_string();
+    Ok((is_confirmed(&response), reason))
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+/// 检查暂存改动是否适合作为 fixup 提交，并按用户确认创建 fixup 提交
+pub async fn run(options: FixupOptions) -> anyhow::Result<()> {
+    if git(&["diff", "--cached", "--name-only"])?.trim().is_empty() {
+        return Err(anyhow::anyhow!("没有已暂存的改动"));
+    }
+
+    let commits = recent_commits(options.depth)?;
+    if commits.is_empty() {
+        println!("没有未推送的本地提交，请使用 commit 生成新的提交信息");
+        return Ok(());
+    }
+
+    let (candidates, total) = rank_candidates(&commits)?;
+    let Some(candidate) = candidates.first() else {
+        println!("暂存的改动与最近 {} 个未推送的提交无关，请使用 commit 生成新的提交信息", commits.len());
+        return Ok(());
+    };
+
+    println!("暂存改动涉及的 {} 行中：", total);
+    for c in &candidates {
+        println!("  {} {}  {} 行", short(&c.sha), c.title, c.lines);
+    }
+
+    if options.no_ai {
+        // 不使用 AI 时，要求至少一半的行来自同一个提交
+        if candidate.lines * 2 < total {
+            println!("没有提交占据多数改动行，请使用 commit 生成新的提交信息");
+            return Ok(());
+        }
+    } else {
+        let (confirmed, reason) = confirm_with_ai(candidate).await?;
+        if !reason.is_empty() {
+            println!("AI 判断: {}", reason);
+        }
+        if !confirmed {
+            println!("暂存的改动不像是对 {} 的修正，请使用 commit 生成新的提交信息", short(&candidate.sha));
+            return Ok(());
+        }
+    }
+
+    println!("建议使用: git commit --fixup={}", short(&candidate.sha));
+    if !prompt::confirm("是否创建 fixup 提交？", true)? {
+        println!("已取消");
+        return Ok(());
+    }
+
+    // fixup! 提交不需要审查和翻译
+    let status = Command::new("git")
+        .args(["commit", &format!("--fixup={}", candidate.sha)])
+        .env("GIT_COMMIT_HELPER_SKIP_REVIEW", "1")
+        .status()?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("创建 fixup 提交失败"));
+    }
+
+    if options.autosquash || prompt::confirm("是否立即执行 git rebase --autosquash 合并到目标提交？", false)? {
+        autosquash(&candidate.sha)?;
+    }
+    Ok(())
+}
+
+/// 非交互地执行 git rebase -i --autosquash，将 fixup 提交合并到目标提交
+fn autosquash(sha: &str) -> anyhow::Result<()> {
+    let parent = format!("{}^", sha);
+    let has_parent = git(&["rev-parse", "--verify", "-q", &parent]).is_ok();
+    let mut cmd = Command::new("git");
+    cmd.args(["rebase", "-i", "--autosquash", "--autostash"])
+        .arg(if has_parent { parent.as_str() } else { "--root" })
+        .env("GIT_SEQUENCE_EDITOR", ":");
+    if !cmd.status()?.success() {
+        return Err(anyhow::anyhow!("执行 git rebase --autosquash 失败，请手动解决冲突后执行 git rebase --continue"));
+    }
+    println!("已将 fixup 提交合并到 {}", short(sha));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blame_range() {
+        let hunk = |old_start, old_lines| Hunk {
+            header: String::new(),
+            lines: Vec::new(),
+            old_start,
+            old_lines,
+            new_start: 0,
+        };
+        assert_eq!(blame_range(&hunk(10, 3)), (10, 3));
+        assert_eq!(blame_range(&hunk(10, 0)), (10, 2));
+        assert_eq!(blame_range(&hunk(0, 0)), (1, 1));
+    }
+
+    #[test]
+    fn test_parse_blame_and_response() {
+        let output = "1111111111111111111111111111111111111111 3 3 2\nauthor A\nsummary x\n\tline\n1111111111111111111111111111111111111111 4 4\n\tline\n2222222222222222222222222222222222222222 9 5 1\n\tline\n";
+        assert_eq!(parse_blame(output), vec![
+            "1".repeat(40),
+            "1".repeat(40),
+            "2".repeat(40),
+        ]);
+
+        assert!(is_confirmed("YES\nfixes the typo"));
+        assert!(is_confirmed(" yes, it does"));
+        assert!(!is_confirmed("NO\nunrelated"));
+    }
+}
@@ -4,6 +4,9 @@ use log::debug;
 use base64::Engine;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
 
 #[derive(Debug, Deserialize)]
 struct CommitInfo {
@@ -218,3 +221,128 @@ fn add_auth(mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
 
     request
 }
+
+fn git_output(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 判断当前仓库的提交是否需要 Change-Id：
+/// 配置中开启 gerrit_change_id、git config gerrit.createChangeId 为 true、
+/// 仓库根目录存在 .gitreview 或远程地址指向 Gerrit 服务器
+pub fn needs_change_id(config: &crate::config::Config) -> bool {
+    if config.gerrit_change_id {
+        return true;
+    }
+
+    // 与官方 commit-msg hook 一致，允许通过 git config 显式关闭
+    match git_output(&["config", "--type=bool", "gerrit.createChangeId"]).as_deref() {
+        Some("true") => return true,
+        Some("false") => return false,
+        _ => {}
+    }
+
+    if let Some(toplevel) = git_output(&["rev-parse", "--show-toplevel"]) {
+        if Path::new(&toplevel).join(".gitreview").exists() {
+            return true;
+        }
+    }
+
+    git_output(&["remote", "-v"])
+        .is_some_and(|remotes| remotes.lines().any(is_gerrit_remote))
+}
+
+/// 解析远程仓库地址中的主机名和端口，支持 scheme://[user@]host[:port]/path
+/// 和 [user@]host:path 两种形式，本地路径返回 None
+fn remote_host(url: &str) -> Option<(&str, Option<&str>)> {
+    if let Some((_, rest)) = url.split_once("://") {
+        let authority = rest.split('/').next()?;
+        let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => (host, Some(port)),
+            _ => (authority, None),
+        };
+        return (!host.is_empty()).then_some((host, port));
+    }
+    let (authority, _) = url.split_once(':')?;
+    if authority.contains('/') {
+        return None;
+    }
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    (!host.is_empty()).then_some((host, None))
+}
+
+fn is_gerrit_remote(line: &str) -> bool {
+    let url = line.split_whitespace().nth(1).unwrap_or_default();
+    // 29418 是 Gerrit 默认的 SSH 端口；只匹配主机名，不匹配路径
+    remote_host(url).is_some_and(|(host, port)| {
+        port == Some("29418") || host.to_lowercase().split('.').any(|label| label.contains("gerrit"))
+    })
+}
+
+/// 按 Gerrit commit-msg hook 的方式生成 Change-Id：
+/// 对 tree、parent、author、committer 和提交信息组成的内容计算 SHA-1
+pub fn generate_change_id(message: &str, amend: bool) -> Result<String> {
+    let tree = git_output(&["write-tree"])
+        .ok_or_else(|| anyhow::anyhow!("执行 git write-tree 失败"))?;
+    let parent = git_output(&["rev-parse", "-q", "--verify", if amend { "HEAD^1" } else { "HEAD^0" }]);
+    let author = git_output(&["var", "GIT_AUTHOR_IDENT"])
+        .ok_or_else(|| anyhow::anyhow!("无法获取提交作者信息"))?;
+    let committer = git_output(&["var", "GIT_COMMITTER_IDENT"])
+        .ok_or_else(|| anyhow::anyhow!("无法获取提交者信息"))?;
+
+    let input = change_id_input(&tree, parent.as_deref(), &author, &committer, message);
+
+    // 与 Gerrit commit-msg hook 一致，按 blob 计算哈希
+    let mut child = Command::new("git")
+        .args(["hash-object", "-t", "blob", "--stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    child.stdin.take()
+        .ok_or_else(|| anyhow::anyhow!("无法写入 git hash-object 的标准输入"))?
+        .write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("执行 git hash-object 失败"));
+    }
+
+    let change_id = format!("I{}", String::from_utf8_lossy(&output.stdout).trim());
+    debug!("生成 Change-Id: {}", change_id);
+    Ok(change_id)
+}
+
+fn change_id_input(tree: &str, parent: Option<&str>, author: &str, committer: &str, message: &str) -> String {
+    let mut input = format!("tree {}\n", tree);
+    if let Some(parent) = parent {
+        input.push_str(&format!("parent {}\n", parent));
+    }
+    input.push_str(&format!("author {}\ncommitter {}\n\n{}", author, committer, message));
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_gerrit_remote() {
+        assert!(is_gerrit_remote("origin\tssh://user@review.example.com:29418/project (fetch)"));
+        assert!(is_gerrit_remote("origin\thttps://gerrit.uniontech.com/a/project (push)"));
+        assert!(!is_gerrit_remote("origin\tgit@github.com:owner/repo.git (fetch)"));
+        assert!(is_gerrit_remote("origin\tuser@gerrit.example.com:project (fetch)"));
+        assert!(!is_gerrit_remote("origin\tgit@github.com:me/gerrit-tools.git (fetch)"));
+        assert!(!is_gerrit_remote("origin\thttps://github.com/gerrit/project (fetch)"));
+        assert!(!is_gerrit_remote("origin\t/srv/gerrit/project.git (fetch)"));
+    }
+
+    #[test]
+    fn test_change_id_input() {
+        let input = change_id_input("t1", Some("p1"), "A <a@b> 1 +0800", "C <c@d> 2 +0800", "feat: x\n");
+        assert_eq!(input, "tree t1\nparent p1\nauthor A <a@b> 1 +0800\ncommitter C <c@d> 2 +0800\n\nfeat: x\n");
+        assert!(!change_id_input("t1", None, "A", "C", "m").contains("parent"));
+    }
+}
@@ -1,16 +1,19 @@
-use crate::commit::CommitMessage;
-use crate::ai_service;
+use crate::commit::{self, CommitMessage, Translation};
+use crate::ai_service::{self, TextKind};
 use crate::review;
-use crate::config::{self, TranslateDirection};
-use dialoguer::Confirm;
-use log::{debug, info};
+use crate::config;
+use crate::detection::{self, TranslationPlan};
+use crate::language;
+use crate::lint;
+use crate::output;
+use crate::prompt;
+use crate::wrap;
+use log::{debug, info, warn};
 use std::path::Path;
-use textwrap::fill;
 
-const MAX_LINE_LENGTH: usize = 72;
-
-fn is_auto_generated_commit(title: &str) -> bool {
-    let patterns = ["Merge", "Cherry-pick", "Revert"];
+/// 是否为 git 自动生成或 autosquash 使用的提交信息
+pub fn is_auto_generated_commit(title: &str) -> bool {
+    let patterns = ["Merge", "Cherry-pick", "Revert", "fixup!", "squash!", "amend!"];
     patterns.iter().any(|pattern| title.starts_with(pattern))
 }
 
@@ -32,8 +35,9 @@ pub async fn process_commit_msg(path: &Path, no_review: bool) -> anyhow::Result<
         return Ok(());
     }
 
-    // 获取配置并执行代码审查
+    // 获取配置并执行代码审查，按配置的双语排列重新解析
     let config = crate::config::Config::load()?;
+    let msg = CommitMessage::parse_with(&content, &config.bilingual_template);
     if !review::should_skip_review(&msg.title) {
         info!("正在进行代码审查...");
         if let Some(review) = review::review_changes(&config, no_review).await? {
@@ -42,69 +46,142 @@ pub async fn process_commit_msg(path: &Path, no_review: bool) -> anyhow::Result<
         }
     }
 
-    if !contains_chinese(&msg.title) {
-        debug!("未检测到中文内容，跳过翻译");
-        return Ok(());
+    // 已是双语提交信息（如 amend 时再次触发 hook）时不再翻译
+    if msg.is_bilingual(&config.bilingual_template) {
+        debug!("提交信息已是双语，跳过翻译");
+        return lint_commit_msg(path, &config);
     }
 
-    info!("检测到中文内容，准备翻译");
+    // 分别检测标题和正文的语言，与默认语言（提交信息语言中的第一种）不同的部分翻译为默认语言，
+    // 与默认语言相同的部分翻译为另一种语言，再按双语排列模板组合
+    let primary = config.commit_languages().into_iter().next().unwrap_or_else(|| "en".to_string());
+    let Some(plan) = detection::plan(&msg, &primary) else {
+        debug!("提交信息已全部使用默认语言 {}，跳过翻译", primary);
+        return lint_commit_msg(path, &config);
+    };
 
-    if !Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt("检测到提交信息包含中文，是否需要翻译？")
-        .default(true)
-        .interact()? {
-        return Ok(());
+    info!("检测到{}内容，准备翻译", language::display_name(&plan.secondary));
+
+    let question = format!(
+        "检测到提交信息包含{}，是否需要生成{}和{}双语提交信息？",
+        language::display_name(&plan.secondary),
+        language::display_name(&plan.primary),
+        language::display_name(&plan.secondary)
+    );
+    if !prompt::confirm(&question, true)? {
+        return lint_commit_msg(path, &config);
     }
 
-    info!("开始翻译流程，默认使用 {:?} 服务", config.default_service);
+    info!("开始翻译流程（标题 {}），默认使用 {:?} 服务", plan.title, config.default_service);
 
-    // 翻译标题（中译英）
-    let en_title = ai_service::translate_with_fallback(&config, &msg.title, &TranslateDirection::ChineseToEnglish).await?;
-    let en_title = wrap_text(&en_title, MAX_LINE_LENGTH);
-    let original_title = msg.title.clone();
+    // 翻译标题和正文（如果有的话）
+    let translated_title = ai_service::translate_with_fallback(&config, &msg.title, &plan.title, TextKind::CommitMessage).await?;
+    let translated_body = match (&msg.body, &plan.body) {
+        (Some(body), Some(direction)) => Some(ai_service::translate_with_fallback(&config, body, direction, TextKind::CommitMessage).await?),
+        _ => None,
+    };
+    let content = compose_bilingual(msg, &plan, &translated_title, translated_body.as_deref(), &config.bilingual_template);
+
+    info!("翻译完成，正在写入文件");
+    std::fs::write(path, content)?;
+    info!("处理完成");
+    lint_commit_msg(path, &config)
+}
 
-    // 翻译正文（如果有的话）
-    let (en_body, cn_body) = if let Some(body) = &msg.body {
-        let en_body = ai_service::translate_with_fallback(&config, body, &TranslateDirection::ChineseToEnglish).await?;
-        (Some(wrap_text(&en_body, MAX_LINE_LENGTH)), Some(body.clone()))
+/// 按翻译计划将原文和译文组合为双语提交信息：区分默认语言和另一种语言的标题、正文，
+/// 再按双语排列模板排列并折行
+pub fn compose_bilingual(
+    msg: CommitMessage,
+    plan: &TranslationPlan,
+    translated_title: &str,
+    translated_body: Option<&str>,
+    template: &str,
+) -> String {
+    let translated_title = translated_title.trim().to_string();
+    let (primary_title, secondary_title) = if plan.title.target == plan.primary {
+        (translated_title, msg.title)
     } else {
-        (None, None)
+        (msg.title, translated_title)
     };
 
-    // 构建新的消息结构
-    let mut body_parts = Vec::new();
+    let bodies = match (msg.body, &plan.body, translated_body) {
+        (Some(body), Some(direction), Some(translated)) => {
+            let translated = translated.trim().to_string();
+            if direction.target == plan.primary {
+                Some((translated, body))
+            } else {
+                Some((body, translated))
+            }
+        }
+        (body, _, _) => body.map(|b| (b, String::new())),
+    };
 
-    // 添加英文和中文内容
-    if let Some(en_body) = en_body {
-        body_parts.push(en_body);
-        body_parts.push(String::new());
-    }
+    let (primary_body, secondary_body) = bodies.unzip();
+    // 旧版本保存的模板可能缺少占位符，按其排列会丢失原文，改用默认排列
+    let template = commit::parse_bilingual_template(template).unwrap_or_else(|e| {
+        warn!("双语排列模板无效，使用默认排列: {}", e);
+        commit::DEFAULT_BILINGUAL_TEMPLATE.to_string()
+    });
+    let new_msg = CommitMessage {
+        title: primary_title,
+        body: primary_body,
+        trailers: msg.trailers, // 保持原有 trailer 不变
+        translation: Some(Translation {
+            title: secondary_title,
+            body: secondary_body.filter(|b| !b.is_empty()),
+        }),
+    };
+    wrap::wrap_message(&new_msg.format_with(&template), wrap::MAX_LINE_WIDTH)
+}
 
-    body_parts.push(original_title);
+/// prepare-commit-msg hook：在打开编辑器前根据暂存的改动生成提交信息；
+/// 合并、squash、amend 以及通过 -m/-F 指定了提交信息时不处理
+pub async fn prepare_commit_msg(path: &Path, source: Option<&str>) -> anyhow::Result<()> {
+    if let Some(source @ ("message" | "merge" | "squash" | "commit")) = source {
+        debug!("提交信息来源为 {}，跳过生成", source);
+        return Ok(());
+    }
 
-    if let Some(body) = cn_body {
-        body_parts.push(String::new());
-        body_parts.push(wrap_text(&body, MAX_LINE_LENGTH));
+    // 模板中已有内容时保留用户的模板
+    let content = std::fs::read_to_string(path)?;
+    if content.lines().any(|line| !line.trim().is_empty() && !line.starts_with('#')) {
+        debug!("提交信息文件已有内容，跳过生成");
+        return Ok(());
     }
 
-    let new_msg = CommitMessage {
-        title: en_title,
-        body: Some(body_parts.join("\n")),
-        marks: msg.marks, // 保持原有标记不变
+    let print = output::PrintOptions {
+        file: Some(path.to_path_buf()),
+        format: output::OutputFormat::Text,
+        keep_existing: true,
     };
-
-    info!("翻译完成，正在写入文件");
-    std::fs::write(path, new_msg.format())?;
-    info!("处理完成");
+    // 代码审查由 commit-msg hook 负责，这里只生成提交信息
+    let result = commit::generate_commit_message(commit::CommitOptions {
+        no_review: true,
+        print: Some(print),
+        ..Default::default()
+    }).await;
+
+    // 生成失败时不阻止提交，由用户在编辑器中手动填写
+    if let Err(e) = result {
+        eprintln!("警告: 生成提交信息失败，请手动填写: {}", e);
+    }
     Ok(())
 }
 
-fn contains_chinese(text: &str) -> bool {
-    text.chars().any(|c| c as u32 >= 0x4E00 && c as u32 <= 0x9FFF)
-}
+/// 检查最终写入的提交信息，存在错误级别的问题时中止提交
+fn lint_commit_msg(path: &Path, config: &config::Config) -> anyhow::Result<()> {
+    let content = std::fs::read_to_string(path)?;
+    let problems = lint::lint_text(&content, &config.lint);
+    if problems.is_empty() {
+        return Ok(());
+    }
 
-pub fn wrap_text(text: &str, width: usize) -> String {
-    fill(text, width)
+    println!("\n提交信息检查结果:");
+    print!("{}", lint::format_problems(&problems));
+    if problems.iter().any(|p| p.is_error()) {
+        return Err(anyhow::anyhow!("提交信息未通过检查，请修改后重新提交（可使用 git commit --no-verify 跳过）"));
+    }
+    Ok(())
 }
 
 /// 获取上一次提交的差异内容，用于 amend 模式
@@ -122,6 +199,23 @@ pub fn get_last_commit_diff() -> anyhow::Result<String> {
     Ok(String::from_utf8(output.stdout)?)
 }
 
+/// 获取当前分支名，处于分离头指针状态时返回 None
+pub fn get_current_branch() -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["symbolic-ref", "--short", "-q", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if branch.is_empty() {
+        None
+    } else {
+        Some(branch)
+    }
+}
+
 /// 获取上一次提交的信息
 pub fn get_last_commit_message() -> anyhow::Result<String> {
     use std::process::Command;
@@ -1,5 +1,4 @@
 use anyhow::Result;
-use reqwest;
 use serde::Deserialize;
 use log::debug;
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,247 @@
+// 术语表模块
+// 从全局和仓库中的术语表文件读取固定的术语对照，注入翻译和生成提交信息的提示词，
+// 并检查译文和双语提交信息中的术语是否按术语表翻译
+
+use crate::commit::CommitMessage;
+use crate::config::Config;
+use crate::detection;
+use crate::language::{self, LanguagePair};
+use log::{debug, warn};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// 仓库中的术语表文件，位于仓库根目录
+const REPO_GLOSSARY_FILE: &str = ".commit-glossary.json";
+
+/// 一条术语：语言代码到该语言中固定写法的对照，如 {"zh": "任务栏", "en": "taskbar"}
+pub type Entry = BTreeMap<String, String>;
+
+#[derive(Debug, Default)]
+pub struct Glossary {
+    entries: Vec<Entry>,
+}
+
+/// 全局术语表文件，与配置文件位于同一目录
+fn global_path() -> Option<PathBuf> {
+    Config::config_path().ok()?.parent().map(|dir| dir.join("glossary.json"))
+}
+
+fn repo_path() -> Option<PathBuf> {
+    let output = Command::new("git").args(["rev-parse", "--show-toplevel"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let toplevel = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(Path::new(&toplevel).join(REPO_GLOSSARY_FILE))
+}
+
+/// 解析术语表文件内容，语言代码统一为规范写法
+fn parse(content: &str) -> anyhow::Result<Vec<Entry>> {
+    let raw: Vec<BTreeMap<String, String>> = serde_json::from_str(content)?;
+    raw.into_iter()
+        .map(|entry| {
+            entry.into_iter()
+                .filter(|(_, term)| !term.trim().is_empty())
+                .map(|(code, term)| Ok((language::normalize(&code)?, term.trim().to_string())))
+                .collect()
+        })
+        .collect()
+}
+
+fn read(path: &Path) -> Vec<Entry> {
+    if !path.exists() {
+        return Vec::new();
+    }
+    match std::fs::read_to_string(path).map_err(anyhow::Error::from).and_then(|content| parse(&content)) {
+        Ok(entries) => {
+            debug!("已加载术语表 {}，共 {} 条术语", path.display(), entries.len());
+            entries
+        }
+        Err(e) => {
+            warn!("读取术语表 {} 失败: {}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// 当前进程使用的术语表，首次使用时加载
+pub fn current() -> &'static Glossary {
+    static GLOSSARY: OnceLock<Glossary> = OnceLock::new();
+    GLOSSARY.get_or_init(Glossary::load)
+}
+
+/// 术语是否出现在文本中：英文等拉丁字母术语按单词匹配且不区分大小写，其他语言按字符串匹配
+fn contains(text: &str, term: &str) -> bool {
+    if term.is_ascii() {
+        Regex::new(&format!(r"(?i)\b{}\b", regex::escape(term)))
+            .map(|regex| regex.is_match(text))
+            .unwrap_or(false)
+    } else {
+        text.contains(term)
+    }
+}
+
+impl Glossary {
+    /// 加载全局术语表，再用仓库术语表覆盖其中相同的术语
+    pub fn load() -> Self {
+        let mut glossary = Glossary::default();
+        for path in [global_path(), repo_path()].into_iter().flatten() {
+            glossary.merge(read(&path));
+        }
+        glossary
+    }
+
+    /// 合并术语，与已有术语在任一语言中写法相同时替换已有术语
+    fn merge(&mut self, entries: Vec<Entry>) {
+        for entry in entries {
+            self.entries.retain(|existing| !existing.iter().any(|(code, term)| entry.get(code) == Some(term)));
+            self.entries.push(entry);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 术语在指定语言中的写法，没有精确匹配的语言代码时使用主语言，如 en-US 使用 en
+    fn term<'a>(entry: &'a Entry, code: &str) -> Option<&'a str> {
+        entry.get(code)
+            .or_else(|| entry.get(language::primary(code)))
+            .map(|term| term.as_str())
+    }
+
+    /// 翻译提示词中的术语要求，只包含原文中出现的术语
+    pub fn translation_prompt(&self, text: &str, direction: &LanguagePair) -> Option<String> {
+        let lines: Vec<String> = self.entries.iter()
+            .filter_map(|entry| {
+                let source = Self::term(entry, &direction.source)?;
+                let target = Self::term(entry, &direction.target)?;
+                contains(text, source).then(|| format!("- \"{}\" → \"{}\"", source, target))
+            })
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Glossary (always translate these terms exactly as listed):\n{}",
+            lines.join("\n")
+        ))
+    }
+
+    /// 生成提交信息时的术语要求，列出术语在各提交信息语言中的写法
+    pub fn commit_prompt(&self, languages: &[String]) -> Option<String> {
+        let lines: Vec<String> = self.entries.iter()
+            .filter_map(|entry| {
+                let terms: Vec<String> = languages.iter()
+                    .filter_map(|code| Self::term(entry, code).map(|term| format!("{}: {}", code, term)))
+                    .collect();
+                (!terms.is_empty()).then(|| format!("- {}", terms.join(" | ")))
+            })
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// 检查译文：原文中出现的术语在译文中应使用术语表中的写法
+    pub fn check_translation(&self, source: &str, translated: &str, direction: &LanguagePair) -> Vec<String> {
+        self.entries.iter()
+            .filter_map(|entry| {
+                let source_term = Self::term(entry, &direction.source)?;
+                let target_term = Self::term(entry, &direction.target)?;
+                (contains(source, source_term) && !contains(translated, target_term))
+                    .then(|| format!("\"{}\" 应译为 \"{}\"", source_term, target_term))
+            })
+            .collect()
+    }
+
+    /// 检查双语提交信息：一种语言中出现的术语，在另一种语言中应使用对应的写法，template 为双语排列模板
+    pub fn check_message(&self, message: &str, template: &str) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let parsed = CommitMessage::parse_with(message, template);
+        let parts = std::iter::once((&parsed.title, &parsed.body))
+            .chain(parsed.translation.as_ref().map(|t| (&t.title, &t.body)));
+        let texts: Vec<(String, String)> = parts
+            .map(|(title, body)| {
+                let text = [Some(title), body.as_ref()].into_iter().flatten().cloned().collect::<Vec<_>>().join("\n");
+                (detection::detect(&text).unwrap_or("en").to_string(), text)
+            })
+            .collect();
+
+        let mut problems = Vec::new();
+        for (source_language, source) in &texts {
+            for (target_language, target) in &texts {
+                if language::same_language(source_language, target_language) {
+                    continue;
+                }
+                let direction = LanguagePair::new(source_language, target_language);
+                for problem in self.check_translation(source, target, &direction) {
+                    if !problems.contains(&problem) {
+                        problems.push(problem);
+                    }
+                }
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commit::DEFAULT_BILINGUAL_TEMPLATE;
+
+    fn glossary() -> Glossary {
+        let mut glossary = Glossary::default();
+        glossary.merge(parse(r#"[
+            {"zh": "任务栏", "en": "taskbar"},
+            {"zh": "控制中心", "EN": "Control Center", "ja": "コントロールセンター"}
+        ]"#).unwrap());
+        glossary
+    }
+
+    #[test]
+    fn test_prompts() {
+        let glossary = glossary();
+        let prompt = glossary.translation_prompt("修复任务栏图标错位", &LanguagePair::new("zh", "en")).unwrap();
+        assert!(prompt.contains("\"任务栏\" → \"taskbar\""));
+        assert!(!prompt.contains("Control Center"));
+        assert_eq!(glossary.translation_prompt("修复登录问题", &LanguagePair::new("zh", "en")), None);
+
+        let prompt = glossary.commit_prompt(&["en-US".to_string(), "ja".to_string()]).unwrap();
+        assert_eq!(prompt, "- en-US: taskbar\n- en-US: Control Center | ja: コントロールセンター");
+    }
+
+    #[test]
+    fn test_check() {
+        let glossary = glossary();
+        let direction = LanguagePair::new("zh", "en");
+        assert!(glossary.check_translation("修复任务栏崩溃", "fix Taskbar crash", &direction).is_empty());
+        assert_eq!(
+            glossary.check_translation("修复任务栏崩溃", "fix task bar crash", &direction),
+            vec!["\"任务栏\" 应译为 \"taskbar\""]
+        );
+
+        let message = "fix: fix dock icon offset\n\n1. Fix icon position\n\nfix: 修复任务栏图标错位\n\n1. 修复图标位置";
+        assert_eq!(glossary.check_message(message, DEFAULT_BILINGUAL_TEMPLATE), vec!["\"任务栏\" 应译为 \"taskbar\""]);
+        assert!(glossary.check_message("fix: fix taskbar icon offset\n\nfix: 修复任务栏图标错位", DEFAULT_BILINGUAL_TEMPLATE).is_empty());
+
+        // 分隔线模板中，note: 开头的正文行不会被当作另一种语言的标题
+        let template = "{title}\n\n{body}\n\n---\n\n{other_title}\n\n{other_body}";
+        let message = "fix: fix dock icon offset\n\nnote: 任务栏 is unchanged\n\n---\n\n修复: 修复任务栏图标错位";
+        assert_eq!(glossary.check_message(message, template), vec!["\"任务栏\" 应译为 \"taskbar\""]);
+        let message = "fix: fix taskbar icon offset\n\nTranslated-Title: 修复任务栏图标错位";
+        assert!(glossary.check_message(message, "{title}\n\n{body}\n\n{other_body}\n\nTranslated-Title: {other_title}").is_empty());
+    }
+
+    #[test]
+    fn test_repo_entries_override_global() {
+        let mut glossary = glossary();
+        glossary.merge(parse(r#"[{"zh": "任务栏", "en": "dock"}]"#).unwrap());
+        assert_eq!(glossary.entries.len(), 2);
+        assert_eq!(Glossary::term(&glossary.entries[1], "en"), Some("dock"));
+    }
+}
@@ -0,0 +1,194 @@
+// 提交历史风格模块
+// 从仓库历史中采样提交信息，作为生成提交信息时的风格示例
+
+use crate::cache;
+use crate::config::CommitStyleConfig;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::process::Command;
+use std::time::Duration;
+
+const CACHE_NAMESPACE: &str = "history";
+/// 每次从历史中读取的提交数量上限，路径匹配在其中进行
+const HISTORY_DEPTH: usize = 200;
+/// 单个示例的最大字符数，避免过长的提交信息占满提示词
+const MAX_EXAMPLE_CHARS: usize = 800;
+
+/// 一条历史提交的摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub message: String,
+    pub files: Vec<String>,
+}
+
+/// 为当前暂存的改动选取风格示例，失败时返回空列表
+pub fn style_examples(config: &CommitStyleConfig, staged_files: &[String]) -> Vec<String> {
+    if !config.enabled || config.sample_size == 0 {
+        return Vec::new();
+    }
+    match load_history(config) {
+        Ok(history) => select_examples(&history, staged_files, config.sample_size),
+        Err(e) => {
+            warn!("读取提交历史失败，跳过风格示例: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// 缓存的提交历史，记录读取时的 HEAD 以便增量刷新
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedHistory {
+    head: String,
+    entries: Vec<HistoryEntry>,
+}
+
+/// 读取仓库提交历史，按仓库路径缓存；HEAD 前进时只读取新增的提交，切换分支或改写历史后重新读取
+fn load_history(config: &CommitStyleConfig) -> anyhow::Result<Vec<HistoryEntry>> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel", "HEAD"])
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("当前目录不是 git 仓库或还没有提交"));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let key = lines.next().unwrap_or_default().to_string();
+    let head = lines.next().unwrap_or_default().to_string();
+
+    let ttl = Duration::from_secs(config.cache_ttl_hours * 3600);
+    let cached = cache::load::<CachedHistory>(CACHE_NAMESPACE, &key, ttl);
+    if let Some(cached) = &cached {
+        if cached.head == head {
+            return Ok(cached.entries.clone());
+        }
+    }
+
+    let entries = match cached.filter(|cached| is_ancestor(&cached.head)) {
+        Some(cached) => {
+            let mut entries = read_log(&format!("{}..HEAD", cached.head))?;
+            debug!("增量读取 {} 条新提交历史", entries.len());
+            entries.extend(cached.entries);
+            entries.truncate(HISTORY_DEPTH);
+            entries
+        }
+        None => {
+            let entries = read_log("HEAD")?;
+            debug!("已读取 {} 条提交历史", entries.len());
+            entries
+        }
+    };
+
+    let cached = CachedHistory { head, entries };
+    if let Err(e) = cache::store(CACHE_NAMESPACE, &key, &cached) {
+        warn!("写入提交历史缓存失败: {}", e);
+    }
+    Ok(cached.entries)
+}
+
+/// 缓存时的 HEAD 是否仍是当前 HEAD 的祖先
+fn is_ancestor(commit: &str) -> bool {
+    Command::new("git")
+        .args(["merge-base", "--is-ancestor", commit, "HEAD"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+fn read_log(range: &str) -> anyhow::Result<Vec<HistoryEntry>> {
+    let output = Command::new("git")
+        .args(["log", "--no-merges", "-n", &HISTORY_DEPTH.to_string(), "--format=%x1e%B%x00", "--name-only", range])
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("执行 git log 命令失败"));
+    }
+    Ok(parse_history(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_history(log: &str) -> Vec<HistoryEntry> {
+    log.split('\x1e')
+        .filter_map(|record| {
+            let (message, files) = record.split_once('\0')?;
+            let message = message.trim();
+            if message.is_empty() {
+                return None;
+            }
+            let message = if message.chars().count() > MAX_EXAMPLE_CHARS {
+                message.chars().take(MAX_EXAMPLE_CHARS).collect::<String>() + "\n..."
+            } else {
+                message.to_string()
+            };
+            Some(HistoryEntry {
+                message,
+                files: files.lines()
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty())
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+/// 优先选取修改过相同文件或目录的提交，不足时用最近的提交补齐，结果保持时间顺序
+fn select_examples(history: &[HistoryEntry], staged_files: &[String], count: usize) -> Vec<String> {
+    let files: HashSet<&str> = staged_files.iter().map(String::as_str).collect();
+    let dirs: HashSet<&str> = staged_files.iter().map(|f| parent_dir(f)).collect();
+
+    let mut scored: Vec<(usize, usize)> = history.iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let score = entry.files.iter()
+                .map(|f| if files.contains(f.as_str()) {
+                    2
+                } else if dirs.contains(parent_dir(f)) {
+                    1
+                } else {
+                    0
+                })
+                .max()
+                .unwrap_or(0);
+            (i, score)
+        })
+        .collect();
+    // 稳定排序：同分时保留时间顺序
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+    let mut selected: Vec<usize> = scored.into_iter().take(count).map(|(i, _)| i).collect();
+    selected.sort_unstable();
+    selected.into_iter().map(|i| history[i].message.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history() {
+        let log = "\x1efeat(ui): add button\n\nbody\n\0\nsrc/ui/button.rs\nREADME.md\n\x1efix: typo\n\0\ndocs/a.md\n";
+        let history = parse_history(log);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].message, "feat(ui): add button\n\nbody");
+        assert_eq!(history[0].files, vec!["src/ui/button.rs", "README.md"]);
+        assert_eq!(history[1].files, vec!["docs/a.md"]);
+    }
+
+    #[test]
+    fn test_select_examples_prefers_same_paths() {
+        let entry = |message: &str, file: &str| HistoryEntry {
+            message: message.to_string(),
+            files: vec![file.to_string()],
+        };
+        let history = vec![
+            entry("recent", "docs/a.md"),
+            entry("same dir", "src/ui/label.rs"),
+            entry("other", "build.rs"),
+            entry("same file", "src/ui/button.rs"),
+        ];
+        let examples = select_examples(&history, &["src/ui/button.rs".to_string()], 3);
+        assert_eq!(examples, vec!["recent", "same dir", "same file"]);
+    }
+}
@@ -1,61 +1,105 @@
 use anyhow::{Context, Result};
-use dialoguer::{Confirm, Select};
+use crate::prompt;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-pub fn install_git_hook(repo_path: Option<PathBuf>, force: bool) -> Result<()> {
+/// 安装的 hook 类型
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum HookMode {
+    /// commit-msg：提交信息写好后翻译、审查和检查
+    CommitMsg,
+    /// prepare-commit-msg：打开编辑器前根据暂存的改动生成提交信息
+    PrepareCommitMsg,
+    /// 同时安装两种 hook
+    Both,
+}
+
+impl HookMode {
+    fn hook_names(self) -> &'static [&'static str] {
+        match self {
+            HookMode::CommitMsg => &["commit-msg"],
+            HookMode::PrepareCommitMsg => &["prepare-commit-msg"],
+            HookMode::Both => &["commit-msg", "prepare-commit-msg"],
+        }
+    }
+}
+
+pub fn install_git_hook(repo_path: Option<PathBuf>, force: bool, mode: Option<HookMode>) -> Result<()> {
     let repo_path = repo_path.unwrap_or_else(|| PathBuf::from("."));
     let git_dir = find_git_dir(&repo_path)?;
     let hooks_dir = git_dir.join("hooks");
-    let commit_msg_hook = hooks_dir.join("commit-msg");
+
+    let mode = match mode {
+        Some(mode) => mode,
+        None => {
+            let options = [
+                "commit-msg：提交时翻译并检查手动编写的提交信息",
+                "prepare-commit-msg：git commit 打开编辑器时预先填入 AI 生成的提交信息",
+                "同时安装以上两种 hook",
+            ];
+            match prompt::select("请选择要安装的 hook", &options, 0)? {
+                0 => HookMode::CommitMsg,
+                1 => HookMode::PrepareCommitMsg,
+                _ => HookMode::Both,
+            }
+        }
+    };
+
+    // 获取当前二进制的路径
+    let current_exe = std::env::current_exe()?;
+    let binary_path = current_exe.canonicalize()?;
+
+    for hook_name in mode.hook_names() {
+        install_hook(&hooks_dir, hook_name, &binary_path, force)?;
+    }
+    Ok(())
+}
+
+fn install_hook(hooks_dir: &Path, hook_name: &str, binary_path: &Path, force: bool) -> Result<()> {
+    let hook_path = hooks_dir.join(hook_name);
+    let backup_path = hooks_dir.join(format!("{}.old", hook_name));
 
     // 处理已存在的 hook
-    let (use_backup, run_before) = if commit_msg_hook.exists() {
+    let (use_backup, run_before) = if hook_path.exists() {
         if force {
-            let (keep_old, run_before) = handle_existing_hook(&commit_msg_hook)?;
+            let (keep_old, run_before) = handle_existing_hook(&hook_path, &backup_path)?;
             (keep_old, if keep_old { Some(run_before) } else { None })
         } else {
             return Err(anyhow::anyhow!(
                 "Hook 文件已存在: {}。使用 --force 选项进行处理。",
-                commit_msg_hook.display()
+                hook_path.display()
             ));
         }
     } else {
         (false, None)
     };
 
-    // 获取当前二进制的路径
-    let current_exe = std::env::current_exe()?;
-    let binary_path = current_exe.canonicalize()?;
-
-    // 创建新的 hook 内容
-    let hook_content = create_hook_content(&binary_path, use_backup, run_before)?;
+    // 创建新的 hook 内容，备份 hook 使用绝对路径
+    let backup_path = backup_path.canonicalize().unwrap_or(backup_path);
+    let hook_content = create_hook_content(binary_path, hook_name, use_backup.then_some(backup_path.as_path()), run_before)?;
 
     // 确保 hooks 目录存在
-    fs::create_dir_all(&hooks_dir)?;
+    fs::create_dir_all(hooks_dir)?;
 
     // 写入 hook 脚本
-    fs::write(&commit_msg_hook, hook_content)?;
+    fs::write(&hook_path, hook_content)?;
 
     // 设置可执行权限
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        fs::set_permissions(&commit_msg_hook, fs::Permissions::from_mode(0o755))?;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))?;
     }
 
-    println!("Git hook 已安装到: {}", commit_msg_hook.display());
+    println!("Git hook 已安装到: {}", hook_path.display());
     Ok(())
 }
 
-fn handle_existing_hook(hook_path: &Path) -> Result<(bool, bool)> {
-    println!("检测到已存在的 commit-msg hook");
+fn handle_existing_hook(hook_path: &Path, backup_path: &Path) -> Result<(bool, bool)> {
+    println!("检测到已存在的 {} hook", hook_path.file_name().unwrap_or_default().to_string_lossy());
 
-    let keep_old = Confirm::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt("是否保留已存在的 hook 功能？")
-        .default(true)
-        .interact()?;
+    let keep_old = prompt::confirm("是否保留已存在的 hook 功能？", true)?;
 
     if !keep_old {
         fs::remove_file(hook_path)?;
@@ -63,62 +107,56 @@ fn handle_existing_hook(hook_path: &Path) -> Result<(bool, bool)> {
     }
 
     // 备份原有 hook 到同目录下
-    let hooks_dir = hook_path.parent().unwrap();
-    let backup_path = hooks_dir.join("commit-msg.old");
-    fs::rename(hook_path, &backup_path)?;
+    fs::rename(hook_path, backup_path)?;
     println!("已存在的 hook 已备份到: {}", backup_path.display());
 
     let options = vec![
-        "先执行翻译程序，再执行原 hook",
-        "先执行原 hook，再执行翻译程序"
+        "先执行本程序，再执行原 hook",
+        "先执行原 hook，再执行本程序"
     ];
-    let selection = Select::with_theme(&dialoguer::theme::ColorfulTheme::default())
-        .with_prompt("请选择执行顺序")
-        .items(&options)
-        .default(0)
-        .interact()?;
+    let selection = prompt::select("请选择执行顺序", &options, 0)?;
 
     Ok((true, selection == 0))
 }
 
-fn create_hook_content(binary_path: &Path, use_backup: bool, run_before: Option<bool>) -> Result<String> {
-    if !use_backup {
-        return Ok(format!(
-            r#"#!/bin/sh
-# 检查是否在命令行中使用了 --no-review 选项
+/// 生成调用本程序的脚本片段，exec 为 false 时失败则中止
+fn run_helper(binary_path: &Path, hook_name: &str, exec: bool) -> String {
+    let (prefix, suffix) = if exec { ("exec ", "") } else { ("", " || exit $?") };
+    if hook_name == "prepare-commit-msg" {
+        return format!(
+            "# 根据暂存的改动生成提交信息\n{}\"{}\" prepare-commit-msg \"$@\"{}\n",
+            prefix, binary_path.display(), suffix
+        );
+    }
+    format!(
+        r#"# 检查是否在命令行中使用了 --no-review 选项
 if git config --bool git-commit-helper.disable-review >/dev/null 2>&1; then
-    exec "{}" --no-review "$1"
+    {prefix}"{binary}" --no-review "$1"{suffix}
 else
-    exec "{}" "$1"
+    {prefix}"{binary}" "$1"{suffix}
 fi
 "#,
-            binary_path.display(),
-            binary_path.display()
-        ));
-    }
+        prefix = prefix,
+        binary = binary_path.display(),
+        suffix = suffix,
+    )
+}
 
-    let git_hooks_dir = binary_path.parent().unwrap().parent().unwrap().join("hooks");
-    let backup_path = git_hooks_dir.join("commit-msg.old");
+fn create_hook_content(binary_path: &Path, hook_name: &str, backup_path: Option<&Path>, run_before: Option<bool>) -> Result<String> {
+    let Some(backup_path) = backup_path else {
+        return Ok(format!("#!/bin/sh\n{}", run_helper(binary_path, hook_name, true)));
+    };
 
     match run_before {
         Some(true) => Ok(format!(
             r#"#!/bin/sh
-# 检查是否在命令行中使用了 --no-review 选项
-if git config --bool git-commit-helper.disable-review >/dev/null 2>&1; then
-    # 先运行当前程序，如果失败则中止
-    "{}" --no-review "$1" || exit $?
-else
-    # 先运行当前程序，如果失败则中止
-    "{}" "$1" || exit $?
-fi
-
+{}
 # 如果存在旧的 hook，则运行它
 if [ -x "{}" ]; then
-    exec "{}" "$1"
+    exec "{}" "$@"
 fi
 "#,
-            binary_path.display(),
-            binary_path.display(),
+            run_helper(binary_path, hook_name, false),
             backup_path.display(),
             backup_path.display()
         )),
@@ -126,22 +164,13 @@ fi
             r#"#!/bin/sh
 # 如果存在旧的 hook，先运行它
 if [ -x "{}" ]; then
-    "{}" "$1" || exit $?
+    "{}" "$@" || exit $?
 fi
 
-# 检查是否在命令行中使用了 --no-review 选项
-if git config --bool git-commit-helper.disable-review >/dev/null 2>&1; then
-    # 运行当前程序
-    exec "{}" --no-review "$1"
-else
-    # 运行当前程序
-    exec "{}" "$1"
-fi
-"#,
+{}"#,
             backup_path.display(),
             backup_path.display(),
-            binary_path.display(),
-            binary_path.display()
+            run_helper(binary_path, hook_name, true)
         )),
         None => unreachable!("使用备份时必须指定运行顺序"),
     }
@@ -162,3 +191,20 @@ fn find_git_dir(start_path: &Path) -> Result<PathBuf> {
     let git_dir = String::from_utf8(output.stdout)?;
     Ok(PathBuf::from(git_dir.trim()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepare_commit_msg_hook_content() {
+        let binary = Path::new("/usr/bin/git-commit-helper");
+        let content = create_hook_content(binary, "prepare-commit-msg", None, None).unwrap();
+        assert_eq!(content, "#!/bin/sh\n# 根据暂存的改动生成提交信息\nexec \"/usr/bin/git-commit-helper\" prepare-commit-msg \"$@\"\n");
+
+        let backup = Path::new("/repo/.git/hooks/prepare-commit-msg.old");
+        let content = create_hook_content(binary, "prepare-commit-msg", Some(backup), Some(true)).unwrap();
+        assert!(content.contains("\"/usr/bin/git-commit-helper\" prepare-commit-msg \"$@\" || exit $?\n"));
+        assert!(content.contains("exec \"/repo/.git/hooks/prepare-commit-msg.old\" \"$@\""));
+    }
+}
@@ -0,0 +1,494 @@
+// issue 引用模块
+// 表示提交信息中的 issue 引用字段（如 Fixes:、PMS:），通过可配置的 issue 跟踪系统规则
+// 解析 --issues 参数中的链接，并支持从分支名中自动识别
+
+use crate::cache;
+use crate::config::{BranchIssuePattern, IssueContextConfig, IssueTracker, JiraConfig};
+use crate::terminal_format::print_progress;
+use log::{debug, warn};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::time::Duration;
+
+const CACHE_NAMESPACE: &str = "issues";
+
+/// 一个 issue 引用，trailer 为字段名，value 为引用内容
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueRef {
+    pub trailer: String,
+    pub value: String,
+}
+
+impl IssueRef {
+    pub fn new(trailer: &str, value: &str) -> Self {
+        IssueRef {
+            trailer: trailer.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// 合并重复的引用，保留首次出现的顺序
+pub fn merge_refs(refs: impl IntoIterator<Item = IssueRef>) -> Vec<IssueRef> {
+    let mut result: Vec<IssueRef> = Vec::new();
+    for r in refs {
+        if !result.contains(&r) {
+            result.push(r);
+        }
+    }
+    result
+}
+
+/// 将引用格式化为提交信息字段，同名字段合并为一行，Fixes 排在最前
+pub fn format_refs(refs: &[IssueRef]) -> String {
+    let mut trailers: Vec<&str> = Vec::new();
+    for r in refs {
+        if !trailers.contains(&r.trailer.as_str()) {
+            trailers.push(&r.trailer);
+        }
+    }
+    trailers.sort_by_key(|t| *t != "Fixes");
+
+    trailers.iter()
+        .map(|trailer| {
+            let values: Vec<&str> = refs.iter()
+                .filter(|r| r.trailer == *trailer)
+                .map(|r| r.value.as_str())
+                .collect();
+            format!("{}: {}", trailer, values.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Jira 规则的 pattern：只配置主机时只识别该主机上的链接，配置了项目 key 时还识别这些项目的编号；
+/// 都未配置时返回 None，避免把 BUG-5678 之类的编号当作 Jira 引用
+fn jira_pattern(jira: &JiraConfig) -> Option<String> {
+    let host = jira.host.as_deref()
+        .map(|h| h.trim().trim_start_matches("https://").trim_start_matches("http://").trim_end_matches('/'))
+        .filter(|h| !h.is_empty())
+        .map(regex::escape);
+    let keys: Vec<String> = jira.project_keys.iter()
+        .map(|k| k.trim().to_uppercase())
+        .filter(|k| !k.is_empty())
+        .map(|k| regex::escape(&k))
+        .collect();
+    match (host, keys.is_empty()) {
+        (None, true) => None,
+        (Some(host), true) => Some(format!(r"^https?://{}/(?:.*/)?browse/(?P<key>[A-Z][A-Z0-9_]+-\d+)$", host)),
+        (host, false) => Some(format!(
+            r"^(?:https?://{}/(?:.*/)?browse/)?(?P<key>(?:{})-\d+)$",
+            host.unwrap_or_else(|| "[^/]+".to_string()),
+            keys.join("|")
+        )),
+    }
+}
+
+/// 内置的 issue 跟踪系统规则，按顺序匹配：特定主机的规则在前，适用于任意主机的禅道和 Redmine 规则在后
+pub fn builtin_trackers(jira: &JiraConfig, current_host: Option<&str>) -> Vec<IssueTracker> {
+    let tracker = |name: &str, pattern: &str, trailer: &str, format: &str, local_format: Option<&str>| IssueTracker {
+        name: name.to_string(),
+        pattern: pattern.to_string(),
+        trailer: trailer.to_string(),
+        format: format.to_string(),
+        local_format: local_format.map(|f| f.to_string()),
+        api: None,
+        title_pointer: None,
+        body_pointer: None,
+        token_env: None,
+    };
+    let github = IssueTracker {
+        api: Some("https://api.github.com/repos/${repo}/issues/${id}".to_string()),
+        token_env: Some("GITHUB_TOKEN".to_string()),
+        ..tracker("github", r"^https?://github\.com/(?P<repo>[^/]+/[^/]+)/issues/(?P<id>\d+)", "Fixes", "${repo}#${id}", Some("#${id}"))
+    };
+    let gitee = IssueTracker {
+        api: Some("https://gitee.com/api/v5/repos/${repo}/issues/${id}".to_string()),
+        ..tracker("gitee", r"^https?://gitee\.com/(?P<repo>[^/]+/[^/]+)/issues/(?P<id>\w+)", "Fixes", "${repo}#${id}", Some("#${id}"))
+    };
+    let mut trackers = vec![
+        // 当前项目的 issue 编号
+        tracker("number", r"^#?(?P<id>\d+)$", "Fixes", "#${id}", None),
+        github,
+        gitee,
+        tracker("gitlab", r"^https?://[^/]+/(?P<repo>.+?)/-/issues/(?P<id>\d+)", "Fixes", "${repo}#${id}", Some("#${id}")),
+        // 联创工程管理系统（基于禅道）
+        tracker("pms-bug", r"^(?:https?://)?pms\.uniontech\.com/.*\bbug-view-(?P<id>\d+)\.html", "PMS", "BUG-${id}", None),
+        tracker("pms-task", r"^(?:https?://)?pms\.uniontech\.com/.*\btask-view-(?P<id>\d+)\.html", "PMS", "TASK-${id}", None),
+        tracker("pms-story", r"^(?:https?://)?pms\.uniontech\.com/.*\bstory-view-(?P<id>\d+)\.html", "PMS", "STORY-${id}", None),
+    ];
+    // 当前仓库所在的其他代码托管平台（如 GitHub Enterprise、Gitea）
+    if let Some(host) = current_host.filter(|host| !["github.com", "gitee.com"].contains(host)) {
+        let pattern = format!(r"^https?://{}/(?P<repo>[^/]+/[^/]+)/issues/(?P<id>\d+)$", regex::escape(host));
+        trackers.push(tracker("origin", &pattern, "Fixes", "${repo}#${id}", Some("#${id}")));
+    }
+    if let Some(pattern) = jira_pattern(jira) {
+        trackers.push(tracker("jira", &pattern, "Jira", "${key}", None));
+    }
+    trackers.extend([
+        // 任意主机上的禅道
+        tracker("zentao-bug", r"^https?://.*(?:\bbug-view-|[?&]m=bug&f=view&bugID=)(?P<id>\d+)", "Zentao", "BUG-${id}", None),
+        tracker("zentao-task", r"^https?://.*(?:\btask-view-|[?&]m=task&f=view&taskID=)(?P<id>\d+)", "Zentao", "TASK-${id}", None),
+        tracker("zentao-story", r"^https?://.*(?:\bstory-view-|[?&]m=story&f=view&storyID=)(?P<id>\d+)", "Zentao", "STORY-${id}", None),
+        // 任意主机上的 Redmine，作为最后的兜底规则
+        tracker("redmine", r"^https?://[^/]+/(?:.*/)?issues/(?P<id>\d+)$", "Redmine", "#${id}", None),
+    ]);
+    trackers
+}
+
+/// 获取 issue 详情所需的信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueSource {
+    pub reference: String,
+    pub api_url: String,
+    pub title_pointer: String,
+    pub body_pointer: String,
+    pub token_env: Option<String>,
+}
+
+/// issue 的标题和描述
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueDetails {
+    pub reference: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// issue 链接解析器，先匹配用户配置的规则，再匹配内置规则
+pub struct IssueRegistry {
+    trackers: Vec<(IssueTracker, Regex)>,
+    current_host: Option<String>,
+    current_repo: Option<String>,
+}
+
+impl IssueRegistry {
+    pub fn new(custom: &[IssueTracker], jira: &JiraConfig) -> Self {
+        Self::with_remote(custom, jira, current_remote())
+    }
+
+    /// 使用指定的远程仓库（主机名、仓库路径）创建解析器
+    fn with_remote(custom: &[IssueTracker], jira: &JiraConfig, remote: Option<(String, String)>) -> Self {
+        let (current_host, current_repo) = remote.unzip();
+        let trackers = custom.iter()
+            .cloned()
+            .chain(builtin_trackers(jira, current_host.as_deref()))
+            .filter_map(|tracker| match Regex::new(&tracker.pattern) {
+                Ok(regex) => Some((tracker, regex)),
+                Err(e) => {
+                    warn!("无效的 issue 跟踪系统规则 {}: {}", tracker.name, e);
+                    None
+                }
+            })
+            .collect();
+        IssueRegistry {
+            trackers,
+            current_host,
+            current_repo,
+        }
+    }
+
+    fn find<'a>(&self, link: &'a str) -> Option<(&IssueTracker, Captures<'a>)> {
+        self.trackers.iter().find_map(|(tracker, regex)| {
+            regex.captures(link).map(|captures| (tracker, captures))
+        })
+    }
+
+    /// 解析单个链接或编号
+    pub fn parse(&self, link: &str) -> Option<IssueRef> {
+        let (tracker, captures) = self.find(link)?;
+        debug!("使用 {} 规则解析 {}", tracker.name, link);
+
+        // 引用当前项目时使用简短格式
+        let is_local = captures.name("repo")
+            .zip(self.current_repo.as_deref())
+            .is_some_and(|(repo, current)| repo.as_str().eq_ignore_ascii_case(current));
+        let format = match &tracker.local_format {
+            Some(local_format) if is_local => local_format,
+            _ => &tracker.format,
+        };
+
+        let mut value = String::new();
+        captures.expand(format, &mut value);
+        Some(IssueRef::new(&tracker.trailer, &value))
+    }
+
+    /// 获取 issue 详情的接口地址，规则未配置 api 时返回 None
+    pub fn source(&self, link: &str) -> Option<IssueSource> {
+        // 当前项目的 issue 编号按 GitHub issue 处理
+        let github_link;
+        let link = match (link.trim_start_matches('#').parse::<u32>(), self.current_host.as_deref(), &self.current_repo) {
+            (Ok(number), Some("github.com"), Some(repo)) => {
+                github_link = format!("https://github.com/{}/issues/{}", repo, number);
+                github_link.as_str()
+            }
+            _ => link,
+        };
+
+        let reference = self.parse(link)?;
+        let (tracker, captures) = self.find(link)?;
+        let api = tracker.api.as_ref()?;
+        let mut api_url = String::new();
+        captures.expand(api, &mut api_url);
+        Some(IssueSource {
+            reference: format!("{}: {}", reference.trailer, reference.value),
+            api_url,
+            title_pointer: tracker.title_pointer.clone().unwrap_or_else(|| "/title".to_string()),
+            body_pointer: tracker.body_pointer.clone().unwrap_or_else(|| "/body".to_string()),
+            token_env: tracker.token_env.clone(),
+        })
+    }
+
+    /// 获取 issues 参数中所有可获取详情的 issue
+    pub fn sources(&self, issues: &str) -> Vec<IssueSource> {
+        split_links(issues).filter_map(|link| self.source(link)).collect()
+    }
+
+    /// 解析 issues 参数，多个链接之间以空格或逗号分隔
+    pub fn parse_all(&self, issues: &str) -> anyhow::Result<Vec<IssueRef>> {
+        let mut refs = Vec::new();
+        for link in split_links(issues) {
+            match self.parse(link) {
+                Some(r) => refs.push(r),
+                None => return Err(anyhow::anyhow!("无法解析 issue 引用格式: {}", link)),
+            }
+        }
+
+        if refs.is_empty() {
+            return Err(anyhow::anyhow!("没有找到有效的 issue 引用"));
+        }
+        Ok(merge_refs(refs))
+    }
+}
+
+/// 按空格和逗号分割多个链接
+fn split_links(issues: &str) -> impl Iterator<Item = &str> {
+    issues.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty())
+}
+
+/// 从 origin 远程地址中提取主机名和仓库路径（如 github.com、owner/repo），支持 HTTPS 和 SSH 格式
+fn current_remote() -> Option<(String, String)> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_remote_url(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let regex = Regex::new(r"^(?:[a-z+]+://(?:[^@/]+@)?([^/:]+)(?::\d+)?/|[^@]+@([^:]+):)(.+?)(?:\.git)?/?$").ok()?;
+    let captures = regex.captures(url)?;
+    let host = captures.get(1).or_else(|| captures.get(2))?.as_str().to_string();
+    Some((host, captures[3].to_string()))
+}
+
+/// 获取 issue 的标题和描述，结果会被缓存；网络不可用或接口出错时跳过该 issue
+pub async fn fetch_details(sources: &[IssueSource], config: &IssueContextConfig, timeout_seconds: u64) -> Vec<IssueDetails> {
+    let ttl = Duration::from_secs(config.cache_ttl_hours * 3600);
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout_seconds))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("创建 HTTP 客户端失败，跳过获取 issue 信息: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut result = Vec::new();
+    for source in sources {
+        if let Some(details) = cache::load::<IssueDetails>(CACHE_NAMESPACE, &source.api_url, ttl) {
+            result.push(details);
+            continue;
+        }
+
+        print_progress(&format!("正在获取 {} 的信息", source.reference), None);
+        let details = fetch_one(&client, source).await;
+        print_progress(&format!("正在获取 {} 的信息", source.reference), Some(100));
+        eprintln!();
+
+        match details {
+            Ok(mut details) => {
+                if details.body.chars().count() > config.max_chars {
+                    details.body = details.body.chars().take(config.max_chars).collect::<String>() + "\n...";
+                }
+                if let Err(e) = cache::store(CACHE_NAMESPACE, &source.api_url, &details) {
+                    warn!("写入 issue 缓存失败: {}", e);
+                }
+                result.push(details);
+            }
+            Err(e) => {
+                eprintln!("警告: 获取 {} 的信息失败，已跳过: {}", source.reference, e);
+            }
+        }
+    }
+    result
+}
+
+async fn fetch_one(client: &reqwest::Client, source: &IssueSource) -> anyhow::Result<IssueDetails> {
+    debug!("获取 issue 信息: {}", source.api_url);
+    let mut request = client.get(&source.api_url)
+        .header("User-Agent", "git-commit-helper")
+        .header("Accept", "application/json");
+    if let Some(token) = source.token_env.as_ref().and_then(|name| std::env::var(name).ok()) {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("接口返回 {}", response.status()));
+    }
+    let json: serde_json::Value = response.json().await?;
+    let text = |pointer: &str| json.pointer(pointer)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+
+    let title = text(&source.title_pointer);
+    if title.is_empty() {
+        return Err(anyhow::anyhow!("接口返回内容中没有标题"));
+    }
+    Ok(IssueDetails {
+        reference: source.reference.clone(),
+        title,
+        body: text(&source.body_pointer),
+    })
+}
+
+/// 使用配置的规则从分支名中识别 issue 引用
+pub fn refs_from_branch(branch: &str, patterns: &[BranchIssuePattern]) -> Vec<IssueRef> {
+    let mut refs = Vec::new();
+    for pattern in patterns {
+        let regex = match Regex::new(&pattern.pattern) {
+            Ok(regex) => regex,
+            Err(e) => {
+                warn!("无效的分支名规则 {}: {}", pattern.pattern, e);
+                continue;
+            }
+        };
+        for captures in regex.captures_iter(branch) {
+            let mut value = String::new();
+            captures.expand(&pattern.format, &mut value);
+            if !value.is_empty() {
+                refs.push(IssueRef::new(&pattern.trailer, &value));
+            }
+        }
+    }
+    merge_refs(refs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_branch_issue_patterns;
+
+    fn jira() -> JiraConfig {
+        JiraConfig { host: Some("jira.example.com".to_string()), project_keys: vec!["proj".to_string()] }
+    }
+
+    fn registry(current_repo: Option<&str>) -> IssueRegistry {
+        let remote = current_repo.map(|repo| ("github.com".to_string(), repo.to_string()));
+        IssueRegistry::with_remote(&[], &jira(), remote)
+    }
+
+    #[test]
+    fn test_builtin_trackers() {
+        let registry = registry(Some("group/sub/project"));
+        let parse = |link: &str| registry.parse(link).map(|r| format!("{}: {}", r.trailer, r.value));
+        assert_eq!(parse("https://github.com/owner/repo/issues/7").as_deref(), Some("Fixes: owner/repo#7"));
+        assert_eq!(parse("https://gitee.com/owner/repo/issues/I4ABCD").as_deref(), Some("Fixes: owner/repo#I4ABCD"));
+        assert_eq!(parse("https://gitlab.example.com/group/sub/project/-/issues/42").as_deref(), Some("Fixes: #42"));
+        assert_eq!(parse("https://jira.example.com/browse/PROJ-123").as_deref(), Some("Jira: PROJ-123"));
+        assert_eq!(parse("PROJ-123").as_deref(), Some("Jira: PROJ-123"));
+        assert_eq!(parse("https://redmine.example.com/issues/99").as_deref(), Some("Redmine: #99"));
+        assert_eq!(parse("https://zentao.example.com/zentao/bug-view-12.html").as_deref(), Some("Zentao: BUG-12"));
+        assert_eq!(parse("http://zentao.example.com/index.php?m=task&f=view&taskID=34").as_deref(), Some("Zentao: TASK-34"));
+        assert_eq!(parse("https://pms.uniontech.com/zentao/story-view-56.html").as_deref(), Some("PMS: STORY-56"));
+        assert_eq!(parse("not-an-issue"), None);
+    }
+
+    #[test]
+    fn test_jira_requires_config() {
+        let parse = |jira: &JiraConfig, link: &str| IssueRegistry::with_remote(&[], jira, None).parse(link);
+        assert_eq!(parse(&JiraConfig::default(), "PROJ-123"), None);
+        assert_eq!(parse(&JiraConfig::default(), "https://jira.example.com/browse/PROJ-123"), None);
+        assert_eq!(parse(&jira(), "BUG-5678"), None);
+
+        let host_only = JiraConfig { host: Some("https://jira.example.com/".to_string()), project_keys: Vec::new() };
+        assert_eq!(parse(&host_only, "https://jira.example.com/browse/BUG-5678"), Some(IssueRef::new("Jira", "BUG-5678")));
+        assert_eq!(parse(&host_only, "BUG-5678"), None);
+        assert_eq!(parse(&host_only, "https://other.example.com/browse/BUG-5678"), None);
+    }
+
+    #[test]
+    fn test_origin_host_before_redmine() {
+        let remote = Some(("github.example.com".to_string(), "owner/repo".to_string()));
+        let registry = IssueRegistry::with_remote(&[], &JiraConfig::default(), remote);
+        assert_eq!(registry.parse("https://github.example.com/owner/repo/issues/5"), Some(IssueRef::new("Fixes", "#5")));
+        assert_eq!(registry.parse("https://github.example.com/other/project/issues/6"), Some(IssueRef::new("Fixes", "other/project#6")));
+        assert_eq!(registry.parse("https://redmine.example.com/issues/99"), Some(IssueRef::new("Redmine", "#99")));
+    }
+
+    #[test]
+    fn test_custom_tracker_takes_precedence() {
+        let custom = IssueTracker {
+            name: "internal".to_string(),
+            pattern: r"^(?P<key>[A-Z]+-\d+)$".to_string(),
+            trailer: "Issue".to_string(),
+            format: "${key}".to_string(),
+            local_format: None,
+            api: None,
+            title_pointer: None,
+            body_pointer: None,
+            token_env: None,
+        };
+        let registry = IssueRegistry::new(&[custom], &JiraConfig::default());
+        assert_eq!(registry.parse("CORE-1"), Some(IssueRef::new("Issue", "CORE-1")));
+        assert_eq!(registry.parse("12"), Some(IssueRef::new("Fixes", "#12")));
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        let remote = |host: &str, repo: &str| Some((host.to_string(), repo.to_string()));
+        assert_eq!(parse_remote_url("https://github.com/owner/repo.git"), remote("github.com", "owner/repo"));
+        assert_eq!(parse_remote_url("git@github.com:owner/repo.git"), remote("github.com", "owner/repo"));
+        assert_eq!(parse_remote_url("ssh://git@gitlab.example.com:2222/group/sub/project"), remote("gitlab.example.com", "group/sub/project"));
+    }
+
+    #[test]
+    fn test_issue_sources() {
+        let registry = registry(Some("owner/repo"));
+        let sources = registry.sources("12 https://github.com/other/project/issues/3 PROJ-1");
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].reference, "Fixes: #12");
+        assert_eq!(sources[0].api_url, "https://api.github.com/repos/owner/repo/issues/12");
+        assert_eq!(sources[1].reference, "Fixes: other/project#3");
+        assert_eq!(sources[1].body_pointer, "/body");
+    }
+
+    #[test]
+    fn test_refs_from_branch() {
+        let patterns = default_branch_issue_patterns();
+        assert_eq!(refs_from_branch("fix/1234-crash", &patterns), vec![IssueRef::new("Fixes", "#1234")]);
+        assert_eq!(refs_from_branch("BUG-5678", &patterns), vec![IssueRef::new("PMS", "BUG-5678")]);
+        assert_eq!(refs_from_branch("feature/task-42-login", &patterns), vec![IssueRef::new("PMS", "TASK-42")]);
+        assert!(refs_from_branch("master", &patterns).is_empty());
+        assert!(refs_from_branch("release/2024", &patterns).is_empty());
+    }
+
+    #[test]
+    fn test_format_refs_groups_trailers() {
+        let refs = merge_refs(vec![
+            IssueRef::new("PMS", "BUG-1"),
+            IssueRef::new("Fixes", "#2"),
+            IssueRef::new("PMS", "TASK-3"),
+            IssueRef::new("Fixes", "#2"),
+        ]);
+        assert_eq!(format_refs(&refs), "Fixes: #2\nPMS: BUG-1 TASK-3");
+    }
+}
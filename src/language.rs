@@ -0,0 +1,224 @@
+// 语言模块
+// 使用 BCP-47 语言代码（如 zh、en、ja、ko、de、zh-TW）表示提交信息和翻译的语言，
+// 提供语言名称和翻译方向
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+/// 已知语言：代码、英文名称（用于提示词）、中文名称（用于界面显示）
+const KNOWN_LANGUAGES: &[(&str, &str, &str)] = &[
+    ("en", "English", "英文"),
+    ("zh", "Chinese", "中文"),
+    ("zh-TW", "Traditional Chinese", "繁体中文"),
+    ("zh-HK", "Traditional Chinese", "繁体中文"),
+    ("zh-Hant", "Traditional Chinese", "繁体中文"),
+    ("ja", "Japanese", "日文"),
+    ("ko", "Korean", "韩文"),
+    ("de", "German", "德文"),
+    ("fr", "French", "法文"),
+    ("es", "Spanish", "西班牙文"),
+    ("ru", "Russian", "俄文"),
+    ("pt", "Portuguese", "葡萄牙文"),
+    ("it", "Italian", "意大利文"),
+    ("vi", "Vietnamese", "越南文"),
+];
+
+/// 规范化 BCP-47 语言代码：语言子标签小写，文字子标签首字母大写，地区子标签大写
+pub fn normalize(code: &str) -> anyhow::Result<String> {
+    let code = code.trim().replace('_', "-");
+    let regex = Regex::new(r"^[A-Za-z]{2,3}(-[A-Za-z0-9]{2,8})*$").unwrap();
+    if !regex.is_match(&code) {
+        return Err(anyhow::anyhow!("无效的语言代码: {}，请使用 BCP-47 格式，例如 zh、en、ja、zh-TW", code));
+    }
+    Ok(code.split('-')
+        .enumerate()
+        .map(|(i, part)| {
+            let alphabetic = part.chars().all(|c| c.is_ascii_alphabetic());
+            match part.len() {
+                _ if i == 0 => part.to_lowercase(),
+                4 if alphabetic => {
+                    let (first, rest) = part.split_at(1);
+                    format!("{}{}", first.to_uppercase(), rest.to_lowercase())
+                }
+                2 if alphabetic => part.to_uppercase(),
+                _ => part.to_lowercase(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-"))
+}
+
+/// 规范化语言列表，提交信息最多使用两种语言
+pub fn normalize_list(codes: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut languages: Vec<String> = Vec::new();
+    for code in codes {
+        let code = normalize(code)?;
+        if !languages.contains(&code) {
+            languages.push(code);
+        }
+    }
+    match languages.len() {
+        0 => Err(anyhow::anyhow!("至少需要指定一种语言")),
+        1 | 2 => Ok(languages),
+        _ => Err(anyhow::anyhow!("提交信息最多使用两种语言，当前指定了 {}", languages.join(","))),
+    }
+}
+
+/// 根据 --only-chinese/--only-english/--lang 参数确定提交信息语言，均未指定时返回空列表，表示使用配置中的默认值
+pub fn from_flags(only_chinese: bool, only_english: bool, languages: Vec<String>) -> Vec<String> {
+    if only_english {
+        vec!["en".to_string()]
+    } else if only_chinese {
+        vec!["zh".to_string()]
+    } else {
+        languages
+    }
+}
+
+/// 主语言子标签，如 zh-TW 返回 zh
+pub fn primary(code: &str) -> &str {
+    code.split('-').next().unwrap_or(code)
+}
+
+/// 两个代码的主语言是否相同
+pub fn same_language(a: &str, b: &str) -> bool {
+    primary(a).eq_ignore_ascii_case(primary(b))
+}
+
+/// 是否为简体中文（zh、zh-CN、zh-Hans 等）
+pub fn is_simplified_chinese(code: &str) -> bool {
+    same_language(code, "zh") && !["TW", "HK", "MO", "Hant"].iter().any(|tag| code.split('-').skip(1).any(|t| t.eq_ignore_ascii_case(tag)))
+}
+
+fn lookup(code: &str) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    KNOWN_LANGUAGES.iter()
+        .find(|(known, _, _)| known.eq_ignore_ascii_case(code))
+        .or_else(|| KNOWN_LANGUAGES.iter().find(|(known, _, _)| known.eq_ignore_ascii_case(primary(code))))
+}
+
+/// 语言的英文名称，用于提示词；未知语言返回代码本身
+pub fn english_name(code: &str) -> String {
+    lookup(code).map(|(_, name, _)| name.to_string()).unwrap_or_else(|| code.to_string())
+}
+
+/// 语言的中文名称，用于界面显示；未知语言返回代码本身
+pub fn display_name(code: &str) -> String {
+    lookup(code).map(|(_, _, name)| name.to_string()).unwrap_or_else(|| code.to_string())
+}
+
+/// 提交信息语言的描述，如 "仅中文"、"英文、中文双语"
+pub fn describe(languages: &[String]) -> String {
+    match languages {
+        [single] => format!("仅{}", display_name(single)),
+        _ => format!("{}双语", languages.iter().map(|l| display_name(l)).collect::<Vec<_>>().join("、")),
+    }
+}
+
+/// 翻译方向：源语言和目标语言
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguagePair {
+    pub source: String,
+    pub target: String,
+}
+
+impl LanguagePair {
+    pub fn new(source: &str, target: &str) -> Self {
+        LanguagePair {
+            source: source.to_string(),
+            target: target.to_string(),
+        }
+    }
+
+    /// 解析翻译方向：兼容 to-english/to-chinese 等旧写法，或使用 "源语言:目标语言"，如 ja:en
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim() {
+            "to-english" | "chinese-to-english" | "中译英" => Ok(Self::new("zh", "en")),
+            "to-chinese" | "english-to-chinese" | "英译中" => Ok(Self::new("en", "zh")),
+            other => {
+                let (source, target) = other.split_once(':')
+                    .ok_or_else(|| anyhow::anyhow!("无效的翻译方向: {}，请使用 '源语言:目标语言'（如 ja:en）、'to-english' 或 'to-chinese'", other))?;
+                let pair = Self::new(&normalize(source)?, &normalize(target)?);
+                if pair.source == pair.target {
+                    return Err(anyhow::anyhow!("源语言和目标语言不能相同"));
+                }
+                Ok(pair)
+            }
+        }
+    }
+}
+
+impl Default for LanguagePair {
+    fn default() -> Self {
+        Self::new("zh", "en")
+    }
+}
+
+impl fmt::Display for LanguagePair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}", display_name(&self.source), display_name(&self.target))
+    }
+}
+
+impl<'de> Deserialize<'de> for LanguagePair {
+    /// 兼容旧版配置中的 "ChineseToEnglish"/"EnglishToChinese"
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Legacy(String),
+            Pair { source: String, target: String },
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Legacy(value) => match value.as_str() {
+                "ChineseToEnglish" => Ok(Self::new("zh", "en")),
+                "EnglishToChinese" => Ok(Self::new("en", "zh")),
+                other => Self::parse(other).map_err(serde::de::Error::custom),
+            },
+            Repr::Pair { source, target } => Ok(Self::new(
+                &normalize(&source).map_err(serde::de::Error::custom)?,
+                &normalize(&target).map_err(serde::de::Error::custom)?,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_names() {
+        assert_eq!(normalize("zh_tw").unwrap(), "zh-TW");
+        assert_eq!(normalize("ZH-hant").unwrap(), "zh-Hant");
+        assert_eq!(normalize(" DE ").unwrap(), "de");
+        assert!(normalize("chinese!").is_err());
+        assert!(normalize_list(&["en".to_string(), "zh".to_string(), "ja".to_string()]).is_err());
+        assert_eq!(normalize_list(&["ja".to_string(), "ja".to_string()]).unwrap(), vec!["ja"]);
+
+        assert_eq!(english_name("zh-CN"), "Chinese");
+        assert_eq!(english_name("zh-TW"), "Traditional Chinese");
+        assert_eq!(display_name("ja"), "日文");
+        assert_eq!(display_name("xx"), "xx");
+        assert!(is_simplified_chinese("zh-CN"));
+        assert!(!is_simplified_chinese("zh-Hant"));
+        assert_eq!(describe(&["zh".to_string()]), "仅中文");
+        assert_eq!(describe(&["en".to_string(), "ja".to_string()]), "英文、日文双语");
+    }
+
+    #[test]
+    fn test_language_pair() {
+        assert_eq!(LanguagePair::parse("to-chinese").unwrap(), LanguagePair::new("en", "zh"));
+        assert_eq!(LanguagePair::parse("ja:EN").unwrap(), LanguagePair::new("ja", "en"));
+        assert!(LanguagePair::parse("en:en").is_err());
+        assert!(LanguagePair::parse("japanese").is_err());
+
+        let legacy: LanguagePair = serde_json::from_str("\"EnglishToChinese\"").unwrap();
+        assert_eq!(legacy, LanguagePair::new("en", "zh"));
+        let pair: LanguagePair = serde_json::from_str(r#"{"source":"ko","target":"en"}"#).unwrap();
+        assert_eq!(pair, LanguagePair::new("ko", "en"));
+        assert_eq!(serde_json::to_string(&pair).unwrap(), r#"{"source":"ko","target":"en"}"#);
+        assert_eq!(pair.to_string(), "韩文 → 英文");
+    }
+}
@@ -1,11 +1,33 @@
 pub mod ai_service;
 pub mod auth;
+pub mod batch;
+pub mod cache;
+pub mod changelog;
 pub mod commit;
 pub mod config;
+pub mod context;
 pub mod debug;
+pub mod detection;
+pub mod diff;
+pub mod document;
+pub mod fixup;
 pub mod git;
 pub mod github;
+pub mod glossary;
 pub mod gerrit;
+pub mod history;
 pub mod install;
+pub mod issue;
+pub mod language;
+pub mod mask;
+pub mod lint;
+pub mod output;
+pub mod prompt;
+pub mod retranslate;
 pub mod review;
+pub mod split;
+pub mod squash;
+pub mod tm;
 pub mod terminal_format;
+pub mod trailer;
+pub mod wrap;
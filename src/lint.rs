@@ -0,0 +1,367 @@
+// 提交信息检查模块
+// 参考 commitlint 的规则设计，对 CommitMessage 进行基于规则的校验
+
+use anyhow::Result;
+use regex::Regex;
+use serde_json::Value;
+use std::process::Command;
+use crate::commit::{self, CommitMessage};
+use crate::config::{LintConfig, LintLevel};
+use crate::git;
+use crate::glossary;
+use crate::terminal_format::Style;
+
+/// 默认允许的提交类型，与提示词模板中的类型保持一致
+const DEFAULT_TYPES: &[&str] = &["feat", "fix", "docs", "style", "refactor", "test", "chore"];
+
+/// 标题结尾不允许出现的标点
+const FULL_STOP_CHARS: &[char] = &['.', '。', '!', '！', '?', '？', ',', '，', ';', '；', ':', '：', '、'];
+
+type RuleCheck = fn(&CommitMessage, &Header, Option<&Value>) -> Option<String>;
+
+struct Rule {
+    name: &'static str,
+    level: LintLevel,
+    check: RuleCheck,
+}
+
+// 内置规则，顺序即输出顺序
+const RULES: &[Rule] = &[
+    Rule { name: "header-max-length", level: LintLevel::Warning, check: check_header_max_length },
+    Rule { name: "type-empty", level: LintLevel::Error, check: check_type_empty },
+    Rule { name: "type-enum", level: LintLevel::Error, check: check_type_enum },
+    Rule { name: "type-case", level: LintLevel::Warning, check: check_type_case },
+    Rule { name: "subject-empty", level: LintLevel::Error, check: check_subject_empty },
+    Rule { name: "subject-full-stop", level: LintLevel::Error, check: check_subject_full_stop },
+    Rule { name: "body-max-line-length", level: LintLevel::Warning, check: check_body_max_line_length },
+    Rule { name: "glossary", level: LintLevel::Warning, check: check_glossary },
+];
+
+/// 检查发现的单个问题
+#[derive(Debug, Clone)]
+pub struct LintProblem {
+    pub rule: &'static str,
+    pub level: LintLevel,
+    pub message: String,
+}
+
+impl LintProblem {
+    pub fn is_error(&self) -> bool {
+        self.level == LintLevel::Error
+    }
+}
+
+/// 标题拆分结果：type(scope)!: subject
+struct Header {
+    r#type: Option<String>,
+    subject: String,
+}
+
+impl Header {
+    fn parse(title: &str) -> Self {
+        let header_regex = Regex::new(r"^([^\s:(!]+)(\([^)]*\))?!?:\s*(.*)$").unwrap();
+        match header_regex.captures(title.trim()) {
+            Some(captures) => Header {
+                r#type: Some(captures[1].to_string()),
+                subject: captures[3].trim().to_string(),
+            },
+            None => Header {
+                r#type: None,
+                subject: title.trim().to_string(),
+            },
+        }
+    }
+}
+
+fn check_header_max_length(msg: &CommitMessage, _header: &Header, value: Option<&Value>) -> Option<String> {
+    let max = value.and_then(|v| v.as_u64()).unwrap_or(50) as usize;
+    let length = msg.title.chars().count();
+    if length > max {
+        Some(format!("标题长度为 {} 个字符，超过了 {} 个字符的限制", length, max))
+    } else {
+        None
+    }
+}
+
+fn check_type_empty(_msg: &CommitMessage, header: &Header, _value: Option<&Value>) -> Option<String> {
+    if header.r#type.is_none() {
+        Some("标题缺少提交类型，应为 type: message 格式".to_string())
+    } else {
+        None
+    }
+}
+
+fn check_type_enum(_msg: &CommitMessage, header: &Header, value: Option<&Value>) -> Option<String> {
+    let commit_type = header.r#type.as_ref()?;
+    let allowed: Vec<String> = match value.and_then(|v| v.as_array()) {
+        Some(types) => types.iter().filter_map(|t| t.as_str().map(|s| s.to_string())).collect(),
+        None => DEFAULT_TYPES.iter().map(|t| t.to_string()).collect(),
+    };
+    if allowed.iter().any(|t| t.eq_ignore_ascii_case(commit_type)) {
+        None
+    } else {
+        Some(format!("提交类型 \"{}\" 不在允许的范围内: {}", commit_type, allowed.join("/")))
+    }
+}
+
+fn check_type_case(_msg: &CommitMessage, header: &Header, _value: Option<&Value>) -> Option<String> {
+    let commit_type = header.r#type.as_ref()?;
+    if *commit_type != commit_type.to_lowercase() {
+        Some(format!("提交类型 \"{}\" 应使用小写", commit_type))
+    } else {
+        None
+    }
+}
+
+fn check_subject_empty(_msg: &CommitMessage, header: &Header, _value: Option<&Value>) -> Option<String> {
+    if header.subject.is_empty() {
+        Some("标题内容不能为空".to_string())
+    } else {
+        None
+    }
+}
+
+fn check_subject_full_stop(_msg: &CommitMessage, header: &Header, _value: Option<&Value>) -> Option<String> {
+    let last = header.subject.chars().last()?;
+    if FULL_STOP_CHARS.contains(&last) {
+        Some(format!("标题结尾不能使用标点符号 \"{}\"", last))
+    } else {
+        None
+    }
+}
+
+fn check_body_max_line_length(msg: &CommitMessage, _header: &Header, value: Option<&Value>) -> Option<String> {
+    let max = value.and_then(|v| v.as_u64()).unwrap_or(72) as usize;
+    let body = msg.full_body()?;
+    // 包含链接的行无法折行，不做限制
+    let long_lines: Vec<usize> = body.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.contains("://") && textwrap::core::display_width(line) > max)
+        .map(|(i, _)| i + 1)
+        .collect();
+    if long_lines.is_empty() {
+        None
+    } else {
+        Some(format!("正文第 {} 行超过了 {} 列的限制",
+            long_lines.iter().map(|n| n.to_string()).collect::<Vec<_>>().join("、"), max))
+    }
+}
+
+fn check_glossary(msg: &CommitMessage, _header: &Header, _value: Option<&Value>) -> Option<String> {
+    let problems = glossary::current().check_message(&msg.format(), commit::DEFAULT_BILINGUAL_TEMPLATE);
+    if problems.is_empty() {
+        None
+    } else {
+        Some(format!("术语未按术语表翻译: {}", problems.join("、")))
+    }
+}
+
+/// 合并默认规则与配置中的覆盖项，返回规则生效的级别与参数
+fn rule_setting<'a>(rule: &Rule, config: &'a LintConfig) -> (LintLevel, Option<&'a Value>) {
+    match config.rules.get(rule.name) {
+        Some(custom) => (custom.level, custom.value.as_ref()),
+        None => (rule.level, None),
+    }
+}
+
+/// 按配置对提交信息执行所有规则检查
+pub fn lint_message(msg: &CommitMessage, config: &LintConfig) -> Vec<LintProblem> {
+    // git 自动生成或 autosquash 使用的提交信息不做检查
+    if !config.enabled || git::is_auto_generated_commit(&msg.title) {
+        return Vec::new();
+    }
+
+    let header = Header::parse(&msg.title);
+    RULES.iter()
+        .filter_map(|rule| {
+            let (level, value) = rule_setting(rule, config);
+            if level == LintLevel::Off {
+                return None;
+            }
+            (rule.check)(msg, &header, value).map(|message| LintProblem {
+                rule: rule.name,
+                level,
+                message,
+            })
+        })
+        .collect()
+}
+
+/// 对文本形式的提交信息执行检查
+pub fn lint_text(content: &str, config: &LintConfig) -> Vec<LintProblem> {
+    lint_message(&CommitMessage::parse(content), config)
+}
+
+/// 将检查结果格式化为终端输出
+pub fn format_problems(problems: &[LintProblem]) -> String {
+    let mut out = String::new();
+    for problem in problems {
+        let line = format!("{} [{}] {}",
+            if problem.is_error() { "✖" } else { "⚠" },
+            problem.rule,
+            problem.message);
+        if problem.is_error() {
+            out.push_str(&Style::red(&line));
+        } else {
+            out.push_str(&Style::yellow(&line));
+        }
+    }
+    out
+}
+
+/// 构建请求 AI 修正提交信息的提示词
+pub fn build_fix_prompt(problems: &[LintProblem]) -> String {
+    let issues = problems.iter()
+        .map(|p| format!("- {}: {}", p.rule, p.message))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(r#"The following git commit message violates these commit message rules:
+{}
+
+Please fix ONLY the listed problems and keep everything else (language, structure, Log and Influence sections) unchanged.
+The title must follow the format "type: message", be at most 50 characters and must not end with punctuation.
+Respond with ONLY the corrected commit message, DO NOT wrap it in any markdown or code block markers."#, issues)
+}
+
+/// lint 子命令：检查提交信息文件、指定的提交范围或最近一次提交
+pub fn run_lint_command(config: &LintConfig, file: Option<&std::path::Path>, range: Option<&str>) -> Result<()> {
+    // 显式执行 lint 子命令时，即使配置中未启用也进行检查
+    let config = &LintConfig { enabled: true, ..config.clone() };
+    let mut error_count = 0;
+    let mut warning_count = 0;
+
+    let messages: Vec<(String, String)> = if let Some(path) = file {
+        vec![(path.display().to_string(), std::fs::read_to_string(path)?)]
+    } else {
+        get_commit_messages(range.unwrap_or("-1"))?
+    };
+
+    for (name, content) in &messages {
+        let msg = CommitMessage::parse(content);
+        let problems = lint_message(&msg, config);
+        if problems.is_empty() {
+            continue;
+        }
+        print!("{}", Style::title(&format!("{} {}", name, msg.title)));
+        print!("{}", format_problems(&problems));
+        error_count += problems.iter().filter(|p| p.is_error()).count();
+        warning_count += problems.iter().filter(|p| !p.is_error()).count();
+    }
+
+    print!("{}", Style::plain(&format!("共检查 {} 条提交信息，发现 {} 个错误，{} 个警告",
+        messages.len(), error_count, warning_count)));
+
+    if error_count > 0 {
+        return Err(anyhow::anyhow!("提交信息检查未通过"));
+    }
+    Ok(())
+}
+
+/// 获取提交范围内（不含合并提交）的所有提交信息，返回 (短哈希, 提交信息)
+fn get_commit_messages(range: &str) -> Result<Vec<(String, String)>> {
+    let mut args = vec!["log", "--no-merges", "--format=%h%x00%B%x1e"];
+    if range.starts_with('-') {
+        // 形如 -1 的数量限制
+        args.push(range);
+    } else {
+        args.push(range);
+        args.push("--");
+    }
+
+    let output = Command::new("git")
+        .args(&args)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("获取提交范围 {} 的提交信息失败: {}",
+            range, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(String::from_utf8(output.stdout)?
+        .split('\x1e')
+        .filter_map(|record| {
+            let record = record.trim_start_matches('\n');
+            let (hash, message) = record.split_once('\0')?;
+            Some((hash.to_string(), message.to_string()))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LintRuleConfig;
+
+    fn enabled() -> LintConfig {
+        LintConfig { enabled: true, ..LintConfig::default() }
+    }
+
+    fn rules_of(content: &str) -> Vec<&'static str> {
+        lint_text(content, &enabled()).iter().map(|p| p.rule).collect()
+    }
+
+    #[test]
+    fn test_lint_disabled_by_default() {
+        assert!(lint_text("Update README.", &LintConfig::default()).is_empty());
+        assert_eq!(rules_of("Update README."), vec!["type-empty", "subject-full-stop"]);
+    }
+
+    #[test]
+    fn test_lint_valid_message() {
+        let content = "feat: add user authentication module\n\n1. Implement JWT-based authentication\n";
+        assert!(rules_of(content).is_empty());
+    }
+
+    #[test]
+    fn test_lint_header_too_long() {
+        let content = "feat: add a very long commit title that certainly exceeds the limit";
+        assert_eq!(rules_of(content), vec!["header-max-length"]);
+    }
+
+    #[test]
+    fn test_lint_missing_type() {
+        let content = "add user authentication module";
+        assert_eq!(rules_of(content), vec!["type-empty"]);
+    }
+
+    #[test]
+    fn test_lint_unknown_type_and_full_stop() {
+        let rules = rules_of("feature: 添加用户认证模块。");
+        assert!(rules.contains(&"type-enum"));
+        assert!(rules.contains(&"subject-full-stop"));
+    }
+
+    #[test]
+    fn test_lint_scope_is_accepted() {
+        assert!(rules_of("fix(config): handle missing file").is_empty());
+    }
+
+    #[test]
+    fn test_lint_body_line_length() {
+        let content = format!("fix: handle missing file\n\n{}\nhttps://example.com/{}\n", "a".repeat(80), "b".repeat(80));
+        let problems = lint_text(&content, &enabled());
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].rule, "body-max-line-length");
+        assert!(!problems[0].is_error());
+    }
+
+    #[test]
+    fn test_lint_skips_merge_commit() {
+        assert!(rules_of("Merge branch 'master' into dev.").is_empty());
+    }
+
+    #[test]
+    fn test_lint_rule_override() {
+        let mut config = enabled();
+        config.rules.insert("header-max-length".to_string(), LintRuleConfig {
+            level: LintLevel::Warning,
+            value: Some(serde_json::json!(100)),
+        });
+        config.rules.insert("type-enum".to_string(), LintRuleConfig {
+            level: LintLevel::Error,
+            value: Some(serde_json::json!(["feat", "fix", "perf"])),
+        });
+        let content = "perf: speed up the translation of very long commit messages a lot";
+        assert!(lint_text(content, &config).is_empty());
+    }
+}
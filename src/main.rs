@@ -1,19 +1,41 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use dialoguer::{Confirm, Select, Input};
+use dialoguer::Input;
 use log::debug;
 use std::path::PathBuf;
 use crate::config::AIService;
 mod terminal_format;
 use terminal_format::Style;
 
+mod batch;
+mod cache;
+mod changelog;
 mod config;
+mod context;
 mod git;
 mod github;
+mod glossary;
 mod gerrit;
 mod install;
+mod issue;
+mod language;
+mod mask;
+mod lint;
+mod output;
+mod prompt;
+mod retranslate;
 mod commit;
+mod detection;
+mod diff;
+mod document;
+mod fixup;
+mod history;
 mod review;
+mod split;
+mod squash;
+mod tm;
+mod trailer;
+mod wrap;
 mod ai_service;
 
 #[derive(Parser)]
@@ -30,6 +52,10 @@ struct Cli {
     /// 禁用代码审查功能
     #[arg(long, global = true)]
     no_review: bool,
+
+    /// 非交互模式，跳过所有确认提示并使用默认选项
+    #[arg(short = 'y', long = "yes", visible_alias = "non-interactive", global = true)]
+    yes: bool,
 }
 
 #[derive(Subcommand, PartialEq)]
@@ -42,9 +68,19 @@ enum Commands {
         /// 设置默认是否只使用英文提交信息，true: 仅英文，false: 中英双语
         #[arg(long = "set-only-english", help = "设置是否默认只使用英文提交信息，true: 仅英文，false: 中英双语")]
         only_english: Option<bool>,
-        /// 设置默认翻译方向：to-english（中译英，默认）或 to-chinese（英译中）
-        #[arg(long = "set-translate-direction", help = "设置默认翻译方向：to-english（中译英）或 to-chinese（英译中）")]
+        /// 设置默认提交信息语言，使用逗号分隔的 BCP-47 代码，最多两种，例如 en,ja 或 de
+        #[arg(long = "set-languages", value_name = "LANGS", value_delimiter = ',')]
+        languages: Option<Vec<String>>,
+        /// 设置默认翻译方向：源语言:目标语言（如 ja:en），或 to-english（中译英）、to-chinese（英译中）
+        #[arg(long = "set-translate-direction", help = "设置默认翻译方向：源语言:目标语言（如 ja:en），或 to-english（中译英）、to-chinese（英译中）")]
         translate_direction: Option<String>,
+        /// 设置 commit-msg hook 中双语提交信息的排列：stacked、other-first、separator、trailer，
+        /// 或包含 {title}、{body}、{other_title}、{other_body} 的模板（\n 表示换行）
+        #[arg(long = "set-bilingual-template", value_name = "TEMPLATE")]
+        bilingual_template: Option<String>,
+        /// 设置代码审查使用的语言（BCP-47 代码），例如 zh、en
+        #[arg(long = "set-review-language", value_name = "LANG")]
+        review_language: Option<String>,
     },
     /// 显示当前配置信息
     Show,
@@ -56,6 +92,19 @@ enum Commands {
         /// 强制安装
         #[arg(short, long)]
         force: bool,
+        /// 安装的 hook 类型，未指定时交互选择
+        #[arg(short, long, value_enum)]
+        mode: Option<install::HookMode>,
+    },
+    /// 由 prepare-commit-msg hook 调用，根据暂存的改动预先生成提交信息
+    #[command(name = "prepare-commit-msg", hide = true)]
+    PrepareCommitMsg {
+        /// 提交信息文件
+        file: PathBuf,
+        /// 提交信息来源（message、template、merge、squash、commit）
+        source: Option<String>,
+        /// 来源为 commit 时的提交 SHA
+        sha: Option<String>,
     },
     /// 管理 AI 服务配置
     #[command(name = "ai")]
@@ -74,11 +123,32 @@ enum Commands {
         /// 要翻译的内容
         content: Option<String>,
         /// 翻译为中文（英译中）
-        #[arg(long = "to-chinese", conflicts_with = "to_english")]
+        #[arg(long = "to-chinese", conflicts_with_all = ["to_english", "to", "from"])]
         to_chinese: bool,
         /// 翻译为英文（中译英，默认）
-        #[arg(long = "to-english", conflicts_with = "to_chinese")]
+        #[arg(long = "to-english", conflicts_with_all = ["to_chinese", "to", "from"])]
         to_english: bool,
+        /// 源语言（BCP-47 代码），未指定时自动检测
+        #[arg(long, value_name = "LANG")]
+        from: Option<String>,
+        /// 目标语言（BCP-47 代码），例如 en、ja、de
+        #[arg(long, value_name = "LANG")]
+        to: Option<String>,
+        /// 为提交范围内的已有提交生成双语提交信息，例如 origin/master..HEAD
+        #[arg(long, value_name = "RANGE", conflicts_with_all = ["file", "text", "content", "to_chinese", "to_english", "from"])]
+        range: Option<String>,
+        /// 翻译提交范围时的输出方式：report 输出报告，notes 写入 git notes，rewrite 改写未推送的提交
+        #[arg(long, value_enum, default_value = "report", requires = "range")]
+        mode: retranslate::RangeOutput,
+        /// 写入 git notes 时使用的 notes 引用
+        #[arg(long, value_name = "REF", default_value = "translations", requires = "range")]
+        notes_ref: String,
+        /// 将译文写入文件；Markdown、.po、.ts 文件默认写入 <文件名>.<目标语言>.<扩展名>
+        #[arg(short, long, value_name = "FILE", conflicts_with = "range")]
+        output: Option<PathBuf>,
+        /// 按格式翻译文件，只翻译其中的文本，默认根据扩展名判断
+        #[arg(long, value_enum, conflicts_with_all = ["text", "range"])]
+        format: Option<document::DocumentFormat>,
     },
     /// 生成提交信息
     #[command(name = "commit")]
@@ -104,6 +174,9 @@ enum Commands {
         /// 仅保留英文提交信息
         #[arg(long = "only-english")]
         only_english: bool,
+        /// 提交信息使用的语言，使用逗号分隔的 BCP-47 代码，最多两种，例如 en,ja 或 de
+        #[arg(long = "lang", value_name = "LANGS", value_delimiter = ',', conflicts_with_all = ["only_chinese", "only_english"])]
+        languages: Vec<String>,
         /// 禁用影响分析（测试建议）
         #[arg(long)]
         no_influence: bool,
@@ -113,6 +186,99 @@ enum Commands {
         /// 关联的GitHub issue或PMS链接
         #[arg(long, value_delimiter = ' ', num_args = 0..)]
         issues: Vec<String>,
+        /// 获取关联 issue 的标题和描述作为生成提交信息的参考
+        #[arg(long)]
+        fetch_issues: bool,
+        /// 将暂存的改动拆分为多个提交
+        #[arg(long, conflicts_with = "amend")]
+        split: bool,
+        /// 添加 Signed-off-by 字段
+        #[arg(short, long)]
+        signoff: bool,
+        /// 添加 Co-authored-by 字段，可使用配置或 .mailmap 中的别名，也可直接指定 "Name <email>"
+        #[arg(long = "co-author", value_name = "ALIAS")]
+        co_authors: Vec<String>,
+        /// 只输出生成的提交信息，不执行提交
+        #[arg(long, visible_alias = "print", conflicts_with = "split")]
+        dry_run: bool,
+        /// 将提交信息写入指定文件（隐含 --dry-run）
+        #[arg(short, long, value_name = "FILE", conflicts_with = "split")]
+        output: Option<PathBuf>,
+        /// 输出格式（json 隐含 --dry-run）
+        #[arg(long, value_enum, default_value = "text", conflicts_with = "split")]
+        format: output::OutputFormat,
+    },
+    /// 为提交范围生成 squash 合并的提交信息
+    Squash {
+        /// 提交范围，例如 main..feature
+        range: String,
+        /// 仅保留中文提交信息
+        #[arg(long = "only-chinese")]
+        only_chinese: bool,
+        /// 仅保留英文提交信息
+        #[arg(long = "only-english")]
+        only_english: bool,
+        /// 提交信息使用的语言，使用逗号分隔的 BCP-47 代码，最多两种，例如 en,ja 或 de
+        #[arg(long = "lang", value_name = "LANGS", value_delimiter = ',', conflicts_with_all = ["only_chinese", "only_english"])]
+        languages: Vec<String>,
+        /// 禁用影响分析（测试建议）
+        #[arg(long)]
+        no_influence: bool,
+        /// 禁用产品日志字段
+        #[arg(long)]
+        no_log: bool,
+        /// 将提交信息写入指定文件
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// 输出格式
+        #[arg(long, value_enum, default_value = "text")]
+        format: output::OutputFormat,
+    },
+    /// 检查暂存的改动是否是对最近某个未推送提交的修正，建议使用 fixup 提交
+    Fixup {
+        /// 检查最近多少个未推送的提交
+        #[arg(short = 'n', long, default_value = "10")]
+        depth: usize,
+        /// 创建 fixup 提交后执行 git rebase --autosquash
+        #[arg(long)]
+        autosquash: bool,
+        /// 不使用 AI 确认，只根据 blame 结果判断
+        #[arg(long)]
+        no_ai: bool,
+    },
+    /// 从提交历史的 Log 字段生成变更日志
+    Changelog {
+        /// 提交范围，例如 v0.9.3..HEAD
+        range: String,
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: changelog::ChangelogFormat,
+        /// 使用的语言：all 或逗号分隔的 BCP-47 代码，默认使用配置中的提交信息语言
+        #[arg(short, long, value_name = "LANGS")]
+        lang: Option<String>,
+        /// 没有 Log 字段的提交使用提交标题
+        #[arg(short, long)]
+        all: bool,
+        /// 使用 AI 精简合并各分组的条目
+        #[arg(long)]
+        summarize: bool,
+        /// 版本号，默认使用范围终点最近的标签
+        #[arg(long, value_name = "VERSION")]
+        release: Option<String>,
+        /// 将变更日志写入指定文件
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// 写入文件时插入到原有内容之前，例如 -o debian/changelog --prepend
+        #[arg(long, requires = "output")]
+        prepend: bool,
+    },
+    /// 检查提交信息是否符合规范
+    Lint {
+        /// 要检查的提交信息文件，默认检查最近一次提交
+        file: Option<PathBuf>,
+        /// 要检查的提交范围，例如 origin/master..HEAD
+        #[arg(short, long, conflicts_with = "file")]
+        range: Option<String>,
     },
     /// 管理 AI 代码审查功能
     #[command(name = "ai-review")]
@@ -158,7 +324,14 @@ enum ServiceCommands {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    if let Err(e) = run().await {
+        eprintln!("Error: {:?}", e);
+        std::process::exit(prompt::exit_code(&e));
+    }
+}
+
+async fn run() -> Result<()> {
     // Add dynamic completion support
     clap_complete::CompleteEnv::with_factory(<Cli as clap::CommandFactory>::command)
         .complete();
@@ -171,24 +344,23 @@ async fn main() -> Result<()> {
 
     debug!("正在启动 git-commit-helper...");
     let cli = Cli::parse();
+    if cli.yes {
+        prompt::set_non_interactive();
+    }
 
     // 检查当前命令是否需要 Gerrit 认证
-    let needs_gerrit = match &cli.input {
-        Some(input) if input.contains("/+/") => true,
-        _ => false,
-    };
+    let needs_gerrit = matches!(&cli.input, Some(input) if input.contains("/+/"));
 
     // 加载配置文件
     let _config = match config::Config::load() {
         Ok(mut config) => {
+            if config.non_interactive {
+                prompt::set_non_interactive();
+            }
             // 如果是 Gerrit URL 且没有配置认证信息，提示用户配置
             if needs_gerrit && config.gerrit.is_none() {
                 println!("检测到 Gerrit URL，但未配置 Gerrit 认证信息。");
-                if Confirm::new()
-                    .with_prompt("是否现在配置 Gerrit 认证？")
-                    .default(true)
-                    .interact()?
-                {
+                if !prompt::is_non_interactive() && prompt::confirm("是否现在配置 Gerrit 认证？", true)? {
                     config.setup_gerrit().await?;
                 }
             }
@@ -201,11 +373,7 @@ async fn main() -> Result<()> {
             } else {
                 println!("{}", Style::red(&format!("错误: {}", e)));
                 println!("{}", Style::yellow("未检测到有效的 AI 配置，需要先进行配置"));
-                if Confirm::new()
-                    .with_prompt("是否现在进行配置？")
-                    .default(true)
-                    .interact()?
-                {
+                if !prompt::is_non_interactive() && prompt::confirm("是否现在进行配置？", true)? {
                     return config::Config::interactive_config().await;
                 }
                 return Err(anyhow::anyhow!("请先运行 'git-commit-helper config' 进行配置"));
@@ -214,7 +382,7 @@ async fn main() -> Result<()> {
     };
 
     match cli.command {
-        Some(Commands::Config { only_chinese, only_english, translate_direction }) => {
+        Some(Commands::Config { only_chinese, only_english, languages, translate_direction, bilingual_template, review_language }) => {
             let mut config = config::Config::load().unwrap_or_else(|_| config::Config::new());
             let mut config_changed = false;
 
@@ -223,14 +391,9 @@ async fn main() -> Result<()> {
                 if only_chinese {
                     config.only_english = false; // 如果设置为仅中文，则清除仅英文标志
                 }
+                config.languages.clear();
                 config_changed = true;
-                let language_mode = if config.only_chinese {
-                    "仅中文"
-                } else if config.only_english {
-                    "仅英文"
-                } else {
-                    "中英双语"
-                };
+                let language_mode = language::describe(&config.commit_languages());
                 println!("{}", Style::green(&format!("已将默认提交信息语言设置为: {}", language_mode)));
             }
 
@@ -239,32 +402,38 @@ async fn main() -> Result<()> {
                 if only_english {
                     config.only_chinese = false; // 如果设置为仅英文，则清除仅中文标志
                 }
+                config.languages.clear();
                 config_changed = true;
-                let language_mode = if config.only_chinese {
-                    "仅中文"
-                } else if config.only_english {
-                    "仅英文"
-                } else {
-                    "中英双语"
-                };
+                let language_mode = language::describe(&config.commit_languages());
+                println!("{}", Style::green(&format!("已将默认提交信息语言设置为: {}", language_mode)));
+            }
+
+            if let Some(languages) = languages {
+                config.languages = language::normalize_list(&languages)?;
+                config.only_chinese = false;
+                config.only_english = false;
+                config_changed = true;
+                let language_mode = language::describe(&config.commit_languages());
                 println!("{}", Style::green(&format!("已将默认提交信息语言设置为: {}", language_mode)));
             }
 
             if let Some(direction_str) = translate_direction {
-                let direction = match direction_str.as_str() {
-                    "to-english" | "chinese-to-english" | "中译英" => config::TranslateDirection::ChineseToEnglish,
-                    "to-chinese" | "english-to-chinese" | "英译中" => config::TranslateDirection::EnglishToChinese,
-                    _ => {
-                        return Err(anyhow::anyhow!("无效的翻译方向，请使用 'to-english'（中译英）或 'to-chinese'（英译中）"));
-                    }
-                };
-                config.translate_direction = direction.clone();
+                let direction = language::LanguagePair::parse(&direction_str)?;
+                println!("{}", Style::green(&format!("已将默认翻译方向设置为: {}", direction)));
+                config.translate_direction = direction;
+                config_changed = true;
+            }
+
+            if let Some(template) = bilingual_template {
+                config.bilingual_template = commit::parse_bilingual_template(&template)?;
+                println!("{}", Style::green(&format!("已将双语排列模板设置为: {:?}", config.bilingual_template)));
+                config_changed = true;
+            }
+
+            if let Some(review_language) = review_language {
+                config.review_language = language::normalize(&review_language)?;
+                println!("{}", Style::green(&format!("已将代码审查使用的语言设置为: {}", language::display_name(&config.review_language))));
                 config_changed = true;
-                let direction_name = match direction {
-                    config::TranslateDirection::ChineseToEnglish => "中译英",
-                    config::TranslateDirection::EnglishToChinese => "英译中",
-                };
-                println!("{}", Style::green(&format!("已将默认翻译方向设置为: {}", direction_name)));
             }
 
             if config_changed {
@@ -295,14 +464,18 @@ async fn main() -> Result<()> {
             }
             Ok(())
         }
-        Some(Commands::Install { path, force }) => {
-            install::install_git_hook(path, force)?;
+        Some(Commands::Install { path, force, mode }) => {
+            install::install_git_hook(path, force, mode)?;
             Ok(())
         }
+        Some(Commands::PrepareCommitMsg { file, source, .. }) => {
+            git::prepare_commit_msg(&file, source.as_deref()).await
+        }
         Some(Commands::AI { command }) => {
             let mut config = config::Config::load().unwrap_or_else(|_| config::Config::new());
             match command {
                 ServiceCommands::Add => {
+                    prompt::require_interactive("添加 AI 服务")?;
                     let selected_service = {
                         println!("\n请选择要添加的 AI 服务:");
                         println!("1) DeepSeek");
@@ -318,7 +491,7 @@ async fn main() -> Result<()> {
                             .report(true)
                             .validate_with(|input: &String| -> Result<(), &str> {
                                 match input.parse::<usize>() {
-                                    Ok(n) if n >= 1 && n <= 7 => Ok(()),
+                                    Ok(n) if (1..=7).contains(&n) => Ok(()),
                                     _ => Err("请输入 1-7 之间的数字")
                                 }
                             })
@@ -371,11 +544,7 @@ async fn main() -> Result<()> {
                         ))
                         .collect();
 
-                    let selection = Select::new()
-                        .with_prompt("请选择要测试的 AI 服务")
-                        .items(&service_names)
-                        .default(0)
-                        .interact()?;
+                    let selection = prompt::select("请选择要测试的 AI 服务", &service_names, 0)?;
 
                     let service = &config.services[selection];
                     println!("{}", Style::title(&format!("正在测试 {:?} 服务...", service.service)));
@@ -383,7 +552,7 @@ async fn main() -> Result<()> {
                     let translator = ai_service::create_translator_for_service(service).await?;
                     let test_text = text.unwrap_or_else(|| "这是一个测试消息，用于验证翻译功能是否正常。".to_string());
                     debug!("开始发送翻译请求");
-                    match translator.translate(&test_text, &config::TranslateDirection::ChineseToEnglish).await {
+                    match translator.translate(&test_text, &language::LanguagePair::default()).await {
                         Ok(result) => {
                             debug!("收到翻译响应");
                             println!("{}", Style::separator());
@@ -411,76 +580,159 @@ async fn main() -> Result<()> {
                 }
             }
         }
-        Some(Commands::Translate { file, text, content, to_chinese, to_english }) => {
+        Some(Commands::Translate { range: Some(range), mode, notes_ref, to, .. }) => {
+            retranslate::run(retranslate::RangeOptions {
+                range,
+                output: mode,
+                notes_ref,
+                language: to.map(|to| language::normalize(&to)).transpose()?,
+            }).await
+        }
+        Some(Commands::Translate { file, text, content, to_chinese, to_english, from, to, output: output_file, format, .. }) => {
             let config = config::Config::load()?;
             if config.services.is_empty() {
                 return Err(anyhow::anyhow!("没有配置任何 AI 服务，请先添加服务"));
             }
 
-            let content = if let Some(file_path) = file {
-                std::fs::read_to_string(file_path)?
+            let (content, input_path) = if let Some(file_path) = file {
+                (std::fs::read_to_string(&file_path)?, Some(file_path))
             } else if let Some(text) = text {
-                text
+                (text, None)
             } else if let Some(content) = content {
                 // 检查内容是否为文件路径
                 let path = PathBuf::from(&content);
                 if path.exists() && path.is_file() {
-                    std::fs::read_to_string(path)?
+                    (std::fs::read_to_string(&path)?, Some(path))
                 } else {
-                    content
+                    (content, None)
                 }
             } else {
                 return Err(anyhow::anyhow!("请提供要翻译的内容"));
             };
 
-            // 确定翻译方向
+            // Markdown、.po、.ts 文件按格式只翻译其中的文本，并写入输出文件
+            if let Some(input) = input_path {
+                if let Some(format) = format.or_else(|| document::DocumentFormat::detect(&input, &content)) {
+                    let to = if to_chinese {
+                        Some("zh".to_string())
+                    } else if to_english {
+                        Some("en".to_string())
+                    } else {
+                        to
+                    };
+                    return document::run(document::DocumentOptions { input, content, format, output: output_file, from, to }).await;
+                }
+            } else if format.is_some() {
+                return Err(anyhow::anyhow!("--format 需要指定要翻译的文件"));
+            }
+
+            // 确定翻译方向：未指定源语言时自动检测，未指定目标语言时使用配置文件中的默认方向
             let direction = if to_chinese {
-                config::TranslateDirection::EnglishToChinese
+                language::LanguagePair::new("en", "zh")
             } else if to_english {
-                config::TranslateDirection::ChineseToEnglish
+                language::LanguagePair::new("zh", "en")
+            } else if from.is_some() || to.is_some() {
+                let source = match from {
+                    Some(from) => language::normalize(&from)?,
+                    None => detection::detect(&content)
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| config.translate_direction.source.clone()),
+                };
+                let target = match to {
+                    Some(to) => language::normalize(&to)?,
+                    None => config.translate_direction.target.clone(),
+                };
+                if source == target {
+                    return Err(anyhow::anyhow!("源语言和目标语言相同（{}），无需翻译", language::display_name(&source)));
+                }
+                language::LanguagePair::new(&source, &target)
             } else {
-                // 使用配置文件中的默认方向
                 config.translate_direction.clone()
             };
 
-            let direction_str = match direction {
-                config::TranslateDirection::ChineseToEnglish => "中译英",
-                config::TranslateDirection::EnglishToChinese => "英译中",
-            };
-
             let service = config.get_default_service()?;
-            println!("{}", Style::title(&format!("正在使用 {:?} 服务进行翻译（{}）...", service.service, direction_str)));
+            println!("{}", Style::title(&format!("正在使用 {:?} 服务进行翻译（{}）...", service.service, direction)));
 
             let translator = ai_service::create_translator_for_service(service).await?;
             match translator.translate(&content, &direction).await {
                 Ok(result) => {
+                    if let Some(path) = output_file {
+                        std::fs::write(&path, format!("{}\n", result.trim_end()))?;
+                        print!("{}", Style::green(&format!("已将译文写入 {}", path.display())));
+                        return Ok(());
+                    }
                     println!("{}", Style::separator());
                     println!("{}", Style::title("翻译结果:"));
                     println!("{}", Style::plain(&format!("原文: {}", content)));
                     println!("{}", Style::green(&format!("译文: {}", result)));
+                    for problem in glossary::current().check_translation(&content, &result, &direction) {
+                        print!("{}", Style::yellow(&format!("⚠ 术语未按术语表翻译: {}", problem)));
+                    }
                     Ok(())
                 }
                 Err(e) => Err(e)
             }
         }
-        Some(Commands::Commit { r#type, message, all, amend, no_translate, only_chinese, only_english, no_influence, no_log, issues }) => {
+        Some(Commands::Commit { r#type, message, all, amend, no_translate, only_chinese, only_english, languages, no_influence, no_log, issues, fetch_issues, split, signoff, co_authors, dry_run, output: output_file, format }) => {
             let issues_str = if issues.is_empty() {
                 None
             } else {
                 Some(issues.join(" "))
             };
-            commit::generate_commit_message(r#type, message, all, amend, cli.no_review, no_translate, only_chinese, only_english, no_influence, no_log, issues_str).await
+            let print = (dry_run || output_file.is_some() || format == output::OutputFormat::Json)
+                .then_some(output::PrintOptions { file: output_file, format, keep_existing: false });
+            commit::generate_commit_message(commit::CommitOptions {
+                commit_type: r#type,
+                message,
+                auto_add: all,
+                amend,
+                no_review: cli.no_review,
+                no_translate,
+                languages: language::from_flags(only_chinese, only_english, languages),
+                no_influence,
+                no_log,
+                issues: issues_str,
+                fetch_issues,
+                split,
+                signoff,
+                co_authors,
+                print,
+            }).await
+        }
+        Some(Commands::Squash { range, only_chinese, only_english, languages, no_influence, no_log, output: output_file, format }) => {
+            let print = output::PrintOptions { file: output_file, format, keep_existing: false };
+            commit::generate_squash_message(&range, language::from_flags(only_chinese, only_english, languages), no_influence, no_log, print).await
+        }
+        Some(Commands::Fixup { depth, autosquash, no_ai }) => {
+            fixup::run(fixup::FixupOptions { depth, autosquash, no_ai }).await
+        }
+        Some(Commands::Changelog { range, format, lang, all, summarize, release, output: output_file, prepend }) => {
+            let language = match lang {
+                Some(value) => changelog::ChangelogLanguage::parse(&value)?,
+                // 未配置时保留全部语言
+                None => config::Config::load()
+                    .map(|config| changelog::ChangelogLanguage::Only(config.commit_languages()))
+                    .unwrap_or(changelog::ChangelogLanguage::All),
+            };
+            changelog::run(changelog::ChangelogOptions {
+                range,
+                format,
+                language,
+                include_all: all,
+                summarize,
+                version: release,
+                output: output_file,
+                prepend,
+            }).await
+        }
+        Some(Commands::Lint { file, range }) => {
+            let config = config::Config::load()?;
+            lint::run_lint_command(&config.lint, file.as_deref(), range.as_deref())
         }
         Some(Commands::AIReview { enable, disable, status }) => {
             let mut config = config::Config::load()?;
             if status {
-                let language_mode = if config.only_chinese {
-                    "仅中文"
-                } else if config.only_english {
-                    "仅英文"
-                } else {
-                    "中英双语"
-                };
+                let language_mode = language::describe(&config.commit_languages());
                 println!("{}", Style::title(&format!("AI 代码审查功能当前状态: {}", if config.ai_review { "已启用" } else { "已禁用" })));
                 println!("{}", Style::plain(&format!("默认提交信息语言: {}", language_mode)));
                 return Ok(());
@@ -0,0 +1,102 @@
+// 翻译保护模块
+// 翻译前将反引号中的代码、URL、双引号中的内容、文件路径和代码标识符替换为占位符，
+// 翻译后还原，并检查译文是否原样保留了每个占位符
+
+use regex::Regex;
+
+/// 替换为占位符后的文本
+#[derive(Debug)]
+pub struct Masked {
+    pub text: String,
+    spans: Vec<String>,
+}
+
+fn placeholder(index: usize) -> String {
+    format!("⟦{}⟧", index)
+}
+
+/// 是否为代码标识符：含下划线、::、->、点号、结尾的 () 或驼峰
+fn is_identifier(word: &str) -> bool {
+    word.contains('_')
+        || word.contains("::")
+        || word.contains("->")
+        || word.contains('.')
+        || word.ends_with("()")
+        || word.as_bytes().windows(2).any(|w| w[0].is_ascii_lowercase() && w[1].is_ascii_uppercase())
+}
+
+/// 将不需要翻译的内容替换为 ⟦0⟧、⟦1⟧ 形式的占位符
+pub fn mask(text: &str) -> Masked {
+    let regex = Regex::new(concat!(
+        r"`[^`\n]+`",
+        r#"|https?://[^\s<>"'`)]+"#,
+        r#"|"[^"\n]+""#,
+        r"|(?:~|\.{1,2})?/[A-Za-z0-9_.-]+(?:/[A-Za-z0-9_.-]+)*/?",
+        r"|[A-Za-z0-9_.-]+(?:/[A-Za-z0-9_.-]+)+/?",
+        r"|[A-Za-z_][A-Za-z0-9_]*(?:(?:::|\.|->)[A-Za-z_][A-Za-z0-9_]*)*(?:\(\))?",
+    )).unwrap();
+
+    let mut masked = String::new();
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for m in regex.find_iter(text) {
+        let matched = m.as_str();
+        let quoted = matched.starts_with('`') || matched.starts_with('"');
+        // 句末的标点不属于路径或 URL
+        let span = if quoted { matched } else { matched.trim_end_matches(['.', ',', ';', ':', '!', '?']) };
+        let is_code = quoted || span.contains('/') || is_identifier(span);
+        if !is_code || span.is_empty() {
+            continue;
+        }
+        masked.push_str(&text[last..m.start()]);
+        masked.push_str(&placeholder(spans.len()));
+        spans.push(span.to_string());
+        last = m.start() + span.len();
+    }
+    masked.push_str(&text[last..]);
+    Masked { text: masked, spans }
+}
+
+impl Masked {
+    pub fn has_placeholders(&self) -> bool {
+        !self.spans.is_empty()
+    }
+
+    /// 将译文中的占位符还原为原文内容，任一占位符缺失或重复时返回错误
+    pub fn restore(&self, translated: &str) -> anyhow::Result<String> {
+        let broken: Vec<String> = (0..self.spans.len())
+            .map(placeholder)
+            .filter(|p| translated.matches(p.as_str()).count() != 1)
+            .collect();
+        if !broken.is_empty() {
+            return Err(anyhow::anyhow!("译文中的占位符缺失或重复: {}", broken.join(" ")));
+        }
+        Ok(self.spans.iter()
+            .enumerate()
+            .fold(translated.to_string(), |result, (i, span)| result.replacen(&placeholder(i), span, 1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_code_and_paths() {
+        let masked = mask("fix: 修复 `parse()` 在 src/main.rs 中读取 \"配置\" 时 readConfig 和 Config::load 崩溃，见 https://example.com/a.");
+        assert_eq!(masked.text, "fix: 修复 ⟦0⟧ 在 ⟦1⟧ 中读取 ⟦2⟧ 时 ⟦3⟧ 和 ⟦4⟧ 崩溃，见 ⟦5⟧.");
+        assert_eq!(masked.spans, vec!["`parse()`", "src/main.rs", "\"配置\"", "readConfig", "Config::load", "https://example.com/a"]);
+
+        let masked = mask("feat(auth): add login page");
+        assert!(!masked.has_placeholders());
+        assert_eq!(masked.text, "feat(auth): add login page");
+    }
+
+    #[test]
+    fn test_restore() {
+        let masked = mask("修复 parse_config 和 ~/.config 的问题");
+        assert_eq!(masked.restore("fix ⟦0⟧ and ⟦1⟧").unwrap(), "fix parse_config and ~/.config");
+        assert!(masked.restore("fix ⟦0⟧ and config").is_err());
+        assert!(masked.restore("fix ⟦0⟧ ⟦0⟧ and ⟦1⟧").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+// 输出模块
+// commit --dry-run 时将生成的提交信息输出到标准输出或文件，
+// 并支持以 JSON 格式输出类型、标题、各语言正文、Log、Influence 和 trailer 等结构化内容
+
+use crate::commit::CommitMessage;
+use crate::detection;
+use crate::trailer::Trailer;
+use regex::Regex;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// 纯文本提交信息
+    Text,
+    /// 结构化的 JSON
+    Json,
+}
+
+/// 仅输出、不提交时的选项
+#[derive(Debug, Clone)]
+pub struct PrintOptions {
+    pub file: Option<PathBuf>,
+    pub format: OutputFormat,
+    pub keep_existing: bool,  // 写入文件时将原有内容（git 的注释说明）保留在提交信息之后
+}
+
+/// 一种语言的提交信息内容
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LanguagePart {
+    pub language: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub log: Option<String>,
+    pub influence: Option<String>,
+}
+
+/// 提交信息的结构化内容
+#[derive(Debug, Serialize)]
+pub struct MessageParts {
+    #[serde(rename = "type")]
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub title: String,
+    pub parts: Vec<LanguagePart>,
+    pub trailers: Vec<Trailer>,
+    pub message: String,
+}
+
+fn title_regex() -> Regex {
+    Regex::new(r"^([a-z]+)(?:\(([^)]*)\))?!?:\s*\S").unwrap()
+}
+
+/// 按双语排列模板将提交信息拆分为结构化内容，另一种语言的部分排在后面
+pub fn parse_parts(message: &str, template: &str) -> MessageParts {
+    let parsed = CommitMessage::parse_with(message, template);
+    let mut parts = vec![parse_language_part(&parsed.title, parsed.body.as_deref())];
+    if let Some(translation) = &parsed.translation {
+        parts.push(parse_language_part(&translation.title, translation.body.as_deref()));
+    }
+
+    let captures = title_regex().captures(&parsed.title);
+    MessageParts {
+        commit_type: captures.as_ref().map(|c| c[1].to_string()),
+        scope: captures.as_ref().and_then(|c| c.get(2)).map(|m| m.as_str().to_string()),
+        title: parsed.title.clone(),
+        parts,
+        trailers: parsed.trailers,
+        message: message.trim_end().to_string(),
+    }
+}
+
+fn parse_language_part(title: &str, body: Option<&str>) -> LanguagePart {
+    enum Field { Body, Log, Influence }
+
+    let mut text = Vec::new();
+    let mut log: Vec<&str> = Vec::new();
+    let mut influence = Vec::new();
+    let mut field = Field::Body;
+
+    for line in body.unwrap_or_default().lines() {
+        if let Some(value) = line.strip_prefix("Log:") {
+            field = Field::Log;
+            log.push(value.trim());
+            continue;
+        }
+        if line.trim() == "Influence:" {
+            field = Field::Influence;
+            continue;
+        }
+        if line.trim().is_empty() && !matches!(field, Field::Body) {
+            field = Field::Body;
+            continue;
+        }
+        match field {
+            Field::Body => text.push(line),
+            Field::Log => log.push(line.trim()),
+            Field::Influence => influence.push(line),
+        }
+    }
+
+    let join = |lines: Vec<&str>, separator: &str| {
+        let text = lines.join(separator).trim().to_string();
+        (!text.is_empty()).then_some(text)
+    };
+    let language = detection::detect(&format!("{}\n{}", title, body.unwrap_or_default())).unwrap_or("en");
+    LanguagePart {
+        language: language.to_string(),
+        title: title.to_string(),
+        body: join(text, "\n"),
+        log: join(log, " "),
+        influence: join(influence, "\n"),
+    }
+}
+
+/// 按选项输出提交信息，未指定文件时输出到标准输出
+pub fn print_message(message: &str, template: &str, options: &PrintOptions) -> anyhow::Result<()> {
+    let content = match options.format {
+        OutputFormat::Text => message.trim_end().to_string(),
+        OutputFormat::Json => serde_json::to_string_pretty(&parse_parts(message, template))?,
+    };
+
+    match &options.file {
+        Some(path) => {
+            let existing = if options.keep_existing {
+                std::fs::read_to_string(path).unwrap_or_default()
+            } else {
+                String::new()
+            };
+            std::fs::write(path, format!("{}\n{}", content, existing))?;
+            eprintln!("提交信息已写入: {}", path.display());
+        }
+        None => println!("{}", content),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commit::DEFAULT_BILINGUAL_TEMPLATE;
+
+    #[test]
+    fn test_parse_bilingual_parts() {
+        let message = "feat(auth): add login\n\n1. Add login endpoint\n\nLog: Added login\n\nInfluence:\n1. Test login\n2. Test logout\n\nfeat(auth): 添加登录\n\n1. 添加登录接口\n\nLog: 新增登录功能\n\nInfluence:\n1. 测试登录\n\nFixes: #12\nChange-Id: I123\n";
+        let parts = parse_parts(message, DEFAULT_BILINGUAL_TEMPLATE);
+        assert_eq!(parts.commit_type.as_deref(), Some("feat"));
+        assert_eq!(parts.scope.as_deref(), Some("auth"));
+        assert_eq!(parts.title, "feat(auth): add login");
+        assert_eq!(parts.parts.len(), 2);
+        assert_eq!(parts.parts[0], LanguagePart {
+            language: "en".to_string(),
+            title: "feat(auth): add login".to_string(),
+            body: Some("1. Add login endpoint".to_string()),
+            log: Some("Added login".to_string()),
+            influence: Some("1. Test login\n2. Test logout".to_string()),
+        });
+        assert_eq!(parts.parts[1].language, "zh");
+        assert_eq!(parts.parts[1].log.as_deref(), Some("新增登录功能"));
+        assert_eq!(parts.trailers, vec![Trailer::new("Fixes", "#12"), Trailer::new("Change-Id", "I123")]);
+    }
+
+    #[test]
+    fn test_parse_title_only() {
+        let parts = parse_parts("chore: bump deps", DEFAULT_BILINGUAL_TEMPLATE);
+        assert_eq!(parts.commit_type.as_deref(), Some("chore"));
+        assert_eq!(parts.scope, None);
+        assert_eq!(parts.parts.len(), 1);
+        assert_eq!(parts.parts[0].body, None);
+        assert!(parts.trailers.is_empty());
+    }
+
+    #[test]
+    fn test_parse_parts_with_template() {
+        let template = "{title}\n\n{body}\n\n---\n\n{other_title}\n\n{other_body}";
+        let message = "feat: add login\n\nnote: keep the old API\n\nLog: Added login\n\n---\n\n功能: 添加登录\n\nLog: 新增登录功能\n";
+        let parts = parse_parts(message, template);
+        assert_eq!(parts.parts.len(), 2);
+        assert_eq!(parts.parts[0].body.as_deref(), Some("note: keep the old API"));
+        assert_eq!(parts.parts[0].log.as_deref(), Some("Added login"));
+        assert_eq!(parts.parts[1].title, "功能: 添加登录");
+        assert_eq!(parts.parts[1].language, "zh");
+
+        let template = "{title}\n\n{body}\n\n{other_body}\n\nTranslated-Title: {other_title}";
+        let parts = parse_parts("fix: crash\n\nbody\n\nTranslated-Title: 修复崩溃\nFixes: #1", template);
+        assert_eq!(parts.parts[1].title, "修复崩溃");
+        assert_eq!(parts.trailers, vec![Trailer::new("Fixes", "#1")]);
+    }
+}
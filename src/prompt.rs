@@ -0,0 +1,128 @@
+// 交互提示模块
+// 统一封装 dialoguer 的确认、选择和输入提示；非交互模式下直接使用默认值，
+// 没有可用终端时立即失败，供脚本和 CI 使用
+
+use dialoguer::{console, theme::ColorfulTheme, Confirm, Input, Select};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 开启非交互模式的环境变量
+pub const NON_INTERACTIVE_ENV: &str = "GIT_COMMIT_HELPER_NON_INTERACTIVE";
+
+/// 需要交互但无法交互时的退出码
+pub const EXIT_INTERACTION_REQUIRED: i32 = 3;
+
+static NON_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// 需要用户交互，但处于非交互模式或没有可用终端
+#[derive(Debug)]
+pub struct InteractionRequired(String);
+
+impl fmt::Display for InteractionRequired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for InteractionRequired {}
+
+/// 开启非交互模式，同时设置环境变量，使 git commit 触发的 hook 进程也以非交互模式运行
+pub fn set_non_interactive() {
+    NON_INTERACTIVE.store(true, Ordering::Relaxed);
+    std::env::set_var(NON_INTERACTIVE_ENV, "1");
+}
+
+/// 是否处于非交互模式（--yes 参数、配置文件或环境变量）
+pub fn is_non_interactive() -> bool {
+    NON_INTERACTIVE.load(Ordering::Relaxed)
+        || std::env::var(NON_INTERACTIVE_ENV).is_ok_and(|v| is_truthy(&v))
+}
+
+fn is_truthy(value: &str) -> bool {
+    !matches!(value.trim().to_lowercase().as_str(), "" | "0" | "false" | "no" | "off")
+}
+
+/// 检查是否可以进行交互，用于没有合理默认值的操作（如配置向导）
+pub fn require_interactive(action: &str) -> anyhow::Result<()> {
+    if is_non_interactive() {
+        return Err(InteractionRequired(format!("非交互模式下无法{}", action)).into());
+    }
+    ensure_terminal(action)
+}
+
+fn ensure_terminal(prompt: &str) -> anyhow::Result<()> {
+    if console::user_attended_stderr() {
+        return Ok(());
+    }
+    Err(InteractionRequired(format!(
+        "需要交互确认（{}），但当前没有可用的终端；可使用 --yes 或设置 {}=1 以非交互模式运行",
+        prompt, NON_INTERACTIVE_ENV
+    )).into())
+}
+
+/// 确认提示，非交互模式下返回默认值
+pub fn confirm(prompt: &str, default: bool) -> anyhow::Result<bool> {
+    if is_non_interactive() {
+        eprintln!("{} {}（非交互模式）", prompt, if default { "是" } else { "否" });
+        return Ok(default);
+    }
+    ensure_terminal(prompt)?;
+    Ok(Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(default)
+        .interact()?)
+}
+
+/// 选择提示，非交互模式下返回默认选项
+pub fn select<T: ToString>(prompt: &str, items: &[T], default: usize) -> anyhow::Result<usize> {
+    if is_non_interactive() {
+        eprintln!("{} {}（非交互模式）", prompt, items[default].to_string());
+        return Ok(default);
+    }
+    ensure_terminal(prompt)?;
+    Ok(Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(items)
+        .default(default)
+        .interact()?)
+}
+
+/// 文本输入提示，非交互模式下返回初始内容
+pub fn input(prompt: &str, initial: &str) -> anyhow::Result<String> {
+    if is_non_interactive() {
+        return Ok(initial.to_string());
+    }
+    ensure_terminal(prompt)?;
+    Ok(Input::<String>::new()
+        .with_prompt(prompt)
+        .with_initial_text(initial)
+        .allow_empty(true)
+        .interact_text()?)
+}
+
+/// 根据错误类型确定进程退出码
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    if error.downcast_ref::<InteractionRequired>().is_some() {
+        EXIT_INTERACTION_REQUIRED
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_truthy_and_exit_code() {
+        assert!(is_truthy("1"));
+        assert!(is_truthy("yes"));
+        assert!(!is_truthy("0"));
+        assert!(!is_truthy("False"));
+        assert!(!is_truthy(""));
+
+        let error: anyhow::Error = InteractionRequired("x".to_string()).into();
+        assert_eq!(exit_code(&error), EXIT_INTERACTION_REQUIRED);
+        assert_eq!(exit_code(&anyhow::anyhow!("other")), 1);
+    }
+}
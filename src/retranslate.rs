@@ -0,0 +1,243 @@
+// 历史提交翻译模块
+// 为提交范围内的已有提交生成双语提交信息：按翻译方向分组批量翻译，
+// 结果可以输出为报告、写入 git notes，或改写尚未推送的提交
+
+use crate::ai_service::TextKind;
+use crate::batch;
+use crate::commit::CommitMessage;
+use crate::config::Config;
+use crate::detection::{self, TranslationPlan};
+use crate::git::{self, compose_bilingual};
+use crate::language::LanguagePair;
+use crate::prompt;
+use crate::squash;
+use clap::ValueEnum;
+use log::debug;
+use std::collections::HashMap;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum RangeOutput {
+    /// 输出双语提交信息报告
+    Report,
+    /// 将译文写入 git notes
+    Notes,
+    /// 改写尚未推送的提交的提交信息
+    Rewrite,
+}
+
+pub struct RangeOptions {
+    pub range: String,
+    pub output: RangeOutput,
+    pub notes_ref: String,
+    pub language: Option<String>,  // 默认语言，未指定时使用提交信息语言中的第一种
+}
+
+/// 需要翻译的提交
+struct Pending {
+    sha: String,
+    message: CommitMessage,
+    plan: TranslationPlan,
+}
+
+/// 一条待翻译的文本：所属提交的序号、是否为正文、翻译方向和原文
+struct Item {
+    index: usize,
+    is_body: bool,
+    direction: LanguagePair,
+    text: String,
+}
+
+fn git_output(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "执行 git {} 失败: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 范围内的提交（按提交顺序，不含合并提交），返回 (完整哈希, 提交信息)
+fn commits_in_range(range: &str) -> anyhow::Result<Vec<(String, String)>> {
+    let log = git_output(&["log", "--reverse", "--no-merges", "--format=%H%x1f%B%x00", range, "--"])?;
+    Ok(log.split('\0')
+        .filter_map(|record| record.trim_start_matches('\n').split_once('\x1f'))
+        .map(|(sha, message)| (sha.to_string(), message.trim().to_string()))
+        .collect())
+}
+
+/// 范围内的提交是否都尚未推送到任何远程分支
+fn all_unpublished(commits: &[String], range: &str) -> anyhow::Result<bool> {
+    let unpublished = git_output(&["rev-list", range, "--not", "--remotes"])?;
+    Ok(commits.iter().all(|sha| unpublished.lines().any(|line| line == sha)))
+}
+
+/// 使用 git filter-branch 改写当前分支上范围内提交的提交信息
+fn rewrite(range: &str, messages: &[(String, String)]) -> anyhow::Result<()> {
+    let (base, head) = squash::parse_range(range)?;
+    let head_sha = git_output(&["rev-parse", &head])?;
+    let current = git_output(&["rev-parse", "HEAD"])?;
+    if head_sha.trim() != current.trim() {
+        return Err(anyhow::anyhow!("改写提交信息要求范围的终点为当前分支的 HEAD，例如 origin/master.."));
+    }
+
+    // 以原提交哈希为文件名保存新的提交信息，msg-filter 中按 $GIT_COMMIT 读取
+    let dir = std::env::temp_dir().join(format!("git-commit-helper-rewrite-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    for (sha, message) in messages {
+        std::fs::write(dir.join(sha), format!("{}\n", message))?;
+    }
+
+    let filter = format!("cat '{}'/\"$GIT_COMMIT\" 2>/dev/null || cat", dir.display());
+    let status = Command::new("git")
+        .args(["filter-branch", "-f", "--msg-filter", &filter, "--", &format!("{}..HEAD", base)])
+        .env("FILTER_BRANCH_SQUELCH_WARNING", "1")
+        .status();
+    let _ = std::fs::remove_dir_all(&dir);
+    if !status?.success() {
+        return Err(anyhow::anyhow!("git filter-branch 执行失败，请确认工作区没有未提交的改动"));
+    }
+    Ok(())
+}
+
+/// 按翻译方向分组，保持各方向首次出现的顺序和组内条目的顺序
+fn group_by_direction(items: &[Item]) -> Vec<(LanguagePair, Vec<&Item>)> {
+    let mut groups: Vec<(LanguagePair, Vec<&Item>)> = Vec::new();
+    for item in items {
+        match groups.iter_mut().find(|(direction, _)| *direction == item.direction) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((item.direction.clone(), vec![item])),
+        }
+    }
+    groups
+}
+
+/// 为提交范围内的提交生成双语提交信息，并按选项输出
+pub async fn run(options: RangeOptions) -> anyhow::Result<()> {
+    let config = Config::load()?;
+    let primary = match options.language {
+        Some(language) => language,
+        None => config.commit_languages().into_iter().next().unwrap_or_else(|| "en".to_string()),
+    };
+
+    let commits = commits_in_range(&options.range)?;
+    if commits.is_empty() {
+        return Err(anyhow::anyhow!("范围 {} 中没有提交", options.range));
+    }
+
+    // 跳过自动生成的提交、已是双语的提交和已使用默认语言的提交
+    let pending: Vec<Pending> = commits.iter()
+        .filter_map(|(sha, raw)| {
+            let message = CommitMessage::parse_with(raw, &config.bilingual_template);
+            if git::is_auto_generated_commit(&message.title) || message.is_bilingual(&config.bilingual_template) {
+                return None;
+            }
+            let plan = detection::plan(&message, &primary)?;
+            Some(Pending { sha: sha.clone(), message, plan })
+        })
+        .collect();
+    eprintln!("范围内共 {} 个提交，其中 {} 个需要翻译", commits.len(), pending.len());
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let items: Vec<Item> = pending.iter()
+        .enumerate()
+        .flat_map(|(index, p)| {
+            let title = Item { index, is_body: false, direction: p.plan.title.clone(), text: p.message.title.clone() };
+            let body = p.message.body.clone().zip(p.plan.body.clone())
+                .map(|(text, direction)| Item { index, is_body: true, direction, text });
+            std::iter::once(title).chain(body)
+        })
+        .collect();
+
+    // 按翻译方向分组，每组分批翻译
+    let mut translated: HashMap<(usize, bool), String> = HashMap::new();
+    for (direction, group) in &group_by_direction(&items) {
+        let texts: Vec<&str> = group.iter().map(|item| item.text.as_str()).collect();
+        let results = batch::translate_all(&config, &texts, direction, TextKind::CommitMessage).await?;
+        for (item, result) in group.iter().zip(results) {
+            translated.insert((item.index, item.is_body), result);
+        }
+    }
+
+    let messages: Vec<(String, String, String)> = pending.into_iter()
+        .enumerate()
+        .map(|(index, p)| {
+            let title = translated.get(&(index, false)).cloned().unwrap_or_default();
+            let body = translated.get(&(index, true)).cloned();
+            // 译文部分单独保存，用于 git notes
+            let note = [Some(title.trim()), body.as_deref().map(str::trim)]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            let message = compose_bilingual(p.message, &p.plan, &title, body.as_deref(), &config.bilingual_template);
+            (p.sha, message, note)
+        })
+        .collect();
+
+    match options.output {
+        RangeOutput::Report => {
+            for (sha, message, _) in &messages {
+                println!("commit {}\n", sha);
+                for line in message.lines() {
+                    println!("    {}", line);
+                }
+                println!();
+            }
+        }
+        RangeOutput::Notes => {
+            for (sha, _, note) in &messages {
+                debug!("为 {} 写入 notes", sha);
+                git_output(&["notes", &format!("--ref={}", options.notes_ref), "add", "-f", "-m", note, sha])?;
+            }
+            println!("已为 {} 个提交写入译文，可使用 git log --notes={} 查看", messages.len(), options.notes_ref);
+        }
+        RangeOutput::Rewrite => {
+            let shas: Vec<String> = commits.iter().map(|(sha, _)| sha.clone()).collect();
+            if !all_unpublished(&shas, &options.range)? {
+                return Err(anyhow::anyhow!("范围内包含已推送的提交，不能改写，请使用 --mode notes 或 report"));
+            }
+            if !prompt::confirm(&format!("是否改写 {} 个提交的提交信息？", messages.len()), false)? {
+                println!("已取消");
+                return Ok(());
+            }
+            let rewritten: Vec<(String, String)> = messages.into_iter().map(|(sha, message, _)| (sha, message)).collect();
+            rewrite(&options.range, &rewritten)?;
+            println!("已改写 {} 个提交的提交信息，原分支备份在 refs/original/ 下", rewritten.len());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_direction() {
+        let item = |index, is_body, source: &str, text: &str| Item {
+            index,
+            is_body,
+            direction: LanguagePair::new(source, "en"),
+            text: text.to_string(),
+        };
+        let items = vec![
+            item(0, false, "zh", "修复崩溃"),
+            item(0, true, "zh", "提前返回"),
+            item(1, false, "ja", "クラッシュを修正"),
+            item(2, false, "zh", "更新文档"),
+        ];
+
+        let groups = group_by_direction(&items);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, LanguagePair::new("zh", "en"));
+        assert_eq!(groups[0].1.iter().map(|i| (i.index, i.is_body)).collect::<Vec<_>>(), vec![(0, false), (0, true), (2, false)]);
+        assert_eq!(groups[1].0, LanguagePair::new("ja", "en"));
+        assert_eq!(groups[1].1.iter().map(|i| i.text.as_str()).collect::<Vec<_>>(), vec!["クラッシュを修正"]);
+    }
+}
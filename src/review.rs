@@ -1,12 +1,36 @@
 use anyhow::Result;
 use std::process::Command;
 use crate::config::Config;
-use crate::ai_service;
+use crate::ai_service::{self, TextKind};
+use crate::detection;
 use crate::github;
 use crate::gerrit;
+use crate::language::{self, LanguagePair};
 use log::{debug, info};
 use crate::terminal_format::Style;
 
+/// 审查结果的开头
+const REVIEW_HEADER: &str = "代码审查报告：";
+
+/// 将改动的标题或描述翻译为审查者使用的语言，已是该语言或无法检测语言时返回 None
+async fn translate_for_reader(config: &Config, text: &str) -> Result<Option<String>> {
+    let target = &config.review_language;
+    let Some(source) = detection::detect(text) else {
+        return Ok(None);
+    };
+    if language::same_language(source, target) {
+        return Ok(None);
+    }
+    let translated = ai_service::translate_with_fallback(config, text, &LanguagePair::new(source, target), TextKind::CommitMessage).await?;
+    Ok(Some(translated))
+}
+
+/// 译文的标签，如 "中文翻译"、"英文翻译"
+fn translation_label(config: &Config) -> String {
+    format!("{}翻译", language::display_name(&config.review_language))
+}
+
+
 pub async fn review_remote_changes(config: &Config, url: &str) -> Result<String> {
     debug!("开始审查远程代码改动: {}", url);
 
@@ -46,29 +70,24 @@ pub async fn review_remote_changes(config: &Config, url: &str) -> Result<String>
 
         let mut info = String::new();
 
-        // 处理标题
-        let title_info = if title.chars().any(|c| c.is_ascii_alphabetic()) {
-            // 如果标题包含英文字符
-            let translator = ai_service::create_translator(config).await?;
-            let prompt = format!("请将以下 PR 标题翻译成中文：\n\n{}", title);
-            let chinese = translator.chat("你是一个代码提交信息翻译助手。", &prompt).await?;
-            format!("标题：{}\n中文翻译：{}\n", title, chinese)
-        } else {
-            format!("标题：{}\n", title)
+        // 处理标题：与审查者使用的语言不同时附上译文
+        let title_info = match translate_for_reader(config, title).await? {
+            Some(translated) => format!("标题：{}\n{}：{}\n", title, translation_label(config), translated.trim()),
+            None => format!("标题：{}\n", title),
         };
         info.push_str(&title_info);
 
         // 处理描述（如果存在）
         if let Some(desc) = description {
             if !desc.trim().is_empty() {
-                if desc.chars().any(|c| c.is_ascii_alphabetic()) {
-                    // 如果描述包含英文字符
-                    let translator = ai_service::create_translator(config).await?;
-                    let prompt = format!("请将以下 PR 描述翻译成中文：\n\n{}", desc);
-                    let chinese = translator.chat("你是一个代码提交信息翻译助手。", &prompt).await?;
-                    info.push_str(&format!("\n描述：\n{}\n中文翻译：\n{}\n", desc, chinese));
-                } else {
-                    info.push_str(&format!("\n描述：\n{}\n", desc));
+                match translate_for_reader(config, desc).await? {
+                    Some(translated) => info.push_str(&format!(
+                        "\n描述：\n{}\n{}：\n{}\n",
+                        desc,
+                        translation_label(config),
+                        translated.trim()
+                    )),
+                    None => info.push_str(&format!("\n描述：\n{}\n", desc)),
                 }
             }
         }
@@ -81,7 +100,7 @@ pub async fn review_remote_changes(config: &Config, url: &str) -> Result<String>
     let translator = ai_service::create_translator(config).await?;
     info!("正在使用 {:?} 服务进行代码审查...", config.default_service);
 
-    let system_prompt = get_review_prompt();
+    let system_prompt = get_review_prompt(config);
     let review_result = translator.chat(&system_prompt, &diff).await?;
     review.push_str(&review_result);
 
@@ -93,7 +112,6 @@ pub async fn review_remote_changes(config: &Config, url: &str) -> Result<String>
 // 终端格式化review内容
 fn format_review_for_terminal(input: &str) -> String {
     let mut out = String::new();
-    let mut in_report = false;
     for line in input.lines() {
         if line.trim().is_empty() {
             out.push('\n');
@@ -102,20 +120,17 @@ fn format_review_for_terminal(input: &str) -> String {
         if line.starts_with("标题：") {
             out.push_str(&Style::separator());
             out.push_str(&Style::title(line));
-        } else if line.starts_with("中文翻译：") {
+        } else if line.split_once('：').is_some_and(|(label, _)| label.ends_with("翻译") && !label.contains(' ')) {
             out.push_str(&Style::green(line));
         } else if line.starts_with("描述：") {
             out.push_str(&Style::blue(line));
-        } else if line.starts_with("代码审查报告：") {
+        } else if line.starts_with(REVIEW_HEADER) {
             out.push_str(&Style::separator());
             out.push_str(&Style::yellow(line));
-            in_report = true;
         } else if line.starts_with("警告") {
             out.push_str(&Style::yellow(line));
         } else if line.starts_with("错误") {
             out.push_str(&Style::red(line));
-        } else if in_report {
-            out.push_str(&Style::plain(line));
         } else {
             out.push_str(&Style::plain(line));
         }
@@ -147,14 +162,28 @@ pub async fn review_changes(config: &Config, no_review: bool) -> Result<Option<S
     let translator = ai_service::create_translator(config).await?;
     info!("正在使用 {:?} 服务进行代码审查...", config.default_service);
 
-    let system_prompt = get_review_prompt();
+    let system_prompt = get_review_prompt(config);
     let review = translator.chat(&system_prompt, &diff).await?;
 
     Ok(Some(review))
 }
 
-// 构建代码审查提示语
-fn get_review_prompt() -> String {
+// 构建代码审查提示语，末尾要求使用审查者的语言撰写审查意见
+fn get_review_prompt(config: &Config) -> String {
+    format!("{}\n\n{}", base_review_prompt().trim_end(), review_language_instruction(&config.review_language))
+}
+
+/// 要求使用指定语言撰写审查意见，开头的 "代码审查报告：" 保持不变以便终端着色
+fn review_language_instruction(code: &str) -> String {
+    format!(
+        "请使用{}撰写审查意见（Write the review in {}），但开头的 \"{}\" 保持原样，代码和标识符不要翻译。",
+        language::display_name(code),
+        language::english_name(code),
+        REVIEW_HEADER
+    )
+}
+
+fn base_review_prompt() -> String {
     // 获取配置文件路径
     let prompt_path = crate::config::Config::config_path()
         .expect("无法获取配置目录")
@@ -174,7 +203,7 @@ fn get_review_prompt() -> String {
     }
 }
 
-const DEFAULT_REVIEW_PROMPT:&str = r#"您是一位专业的代码审查者，请对以下代码变更进行审查并给出中文评价。请着重关注：
+const DEFAULT_REVIEW_PROMPT:&str = r#"您是一位专业的代码审查者，请对以下代码变更进行审查并给出评价。请着重关注：
 
 1. 代码质量：
    - 代码是否清晰易懂
@@ -208,7 +237,7 @@ const DEFAULT_REVIEW_PROMPT:&str = r#"您是一位专业的代码审查者，请
 
 fn get_staged_changes() -> Result<String> {
     let output = Command::new("git")
-        .args(&["diff", "--cached"])
+        .args(["diff", "--cached"])
         .output()?;
 
     if !output.status.success() {
@@ -238,7 +267,7 @@ pub async fn review_local_commit(config: &Config, commit_id: &str) -> Result<Str
     info!("正在使用 {:?} 服务进行代码审查...", config.default_service);
     let mut review = String::new();
     let translator = ai_service::create_translator(config).await?;
-    let system_prompt = get_review_prompt();
+    let system_prompt = get_review_prompt(config);
     let review_result = translator.chat(&system_prompt, &diff).await?;
     review.push_str(&review_result);
 
@@ -247,7 +276,7 @@ pub async fn review_local_commit(config: &Config, commit_id: &str) -> Result<Str
 
 fn get_commit_diff(commit_id: &str) -> Result<String> {
     let output = Command::new("git")
-        .args(&["show", "--pretty=format:", commit_id])
+        .args(["show", "--pretty=format:", commit_id])
         .output()?;
 
     if !output.status.success() {
@@ -0,0 +1,320 @@
+// 拆分提交模块
+// 将已暂存的改动按 hunk 分组，每组单独暂存并提交
+
+use crate::ai_service;
+use crate::diff::{self, FileDiff};
+use crate::prompt;
+use log::debug;
+use serde::Deserialize;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const SPLIT_PROMPT: &str = r#"You are an expert at organizing git commits. The staged changes below are split into numbered hunks.
+Group the hunks into logically separate commits. Each commit should contain one coherent change
+(for example a bug fix, a refactoring, a new feature, documentation or formatting changes).
+
+Rules:
+1. Every hunk must belong to exactly one group
+2. Hunks that depend on each other must be in the same group
+3. Prefer fewer groups when the changes are related
+4. Order the groups so that each commit can be applied on top of the previous ones
+
+Respond with ONLY a JSON object in the following format, without any other text:
+{"groups": [{"summary": "short description of the commit", "hunks": [1, 2]}]}"#;
+
+/// 可独立暂存的最小改动单元，整体处理的文件对应一个单元
+#[derive(Debug, Clone)]
+pub struct Unit {
+    pub file: usize,
+    pub hunk: Option<usize>,
+}
+
+/// 一组改动，对应一次提交
+#[derive(Debug, Clone, Deserialize)]
+pub struct Group {
+    #[serde(default)]
+    pub summary: String,
+    /// 单元编号，从 1 开始
+    #[serde(rename = "hunks")]
+    pub units: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+struct GroupResponse {
+    groups: Vec<Group>,
+}
+
+/// 已暂存改动的拆分计划
+pub struct SplitPlan {
+    pub files: Vec<FileDiff>,
+    pub units: Vec<Unit>,
+    pub groups: Vec<Group>,
+}
+
+impl SplitPlan {
+    pub fn new(diff: &str) -> Self {
+        let files = diff::parse_diff(diff);
+        let units = collect_units(&files);
+        SplitPlan { files, units, groups: Vec::new() }
+    }
+
+    /// 单元的简短描述，用于展示给用户
+    pub fn describe_unit(&self, id: usize) -> String {
+        let unit = &self.units[id - 1];
+        let file = &self.files[unit.file];
+        match unit.hunk {
+            Some(index) => {
+                let hunk = &file.hunks[index];
+                let added = hunk.lines.iter().filter(|l| l.starts_with('+')).count();
+                let removed = hunk.lines.iter().filter(|l| l.starts_with('-')).count();
+                format!("[{}] {} (第 {} 行, +{} -{})", id, file.path, hunk.new_start, added, removed)
+            }
+            None => format!("[{}] {} (整个文件)", id, file.path),
+        }
+    }
+
+    /// 发送给 AI 的单元内容
+    fn unit_text(&self, id: usize) -> String {
+        let unit = &self.units[id - 1];
+        let file = &self.files[unit.file];
+        let body = match unit.hunk {
+            Some(index) => file.hunks[index].text(),
+            None if file.extra.is_empty() => file.hunks.iter()
+                .map(|h| h.text())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => String::from("(binary file)"),
+        };
+        format!("### Hunk {} ({})\n{}\n", id, file.path, body)
+    }
+
+    /// 生成只包含指定单元的补丁
+    pub fn patch_for(&self, unit_ids: &[usize]) -> String {
+        let mut patch = String::new();
+        for (file_index, file) in self.files.iter().enumerate() {
+            let units: Vec<&Unit> = unit_ids.iter()
+                .map(|id| &self.units[id - 1])
+                .filter(|u| u.file == file_index)
+                .collect();
+            if units.is_empty() {
+                continue;
+            }
+            let hunks: Vec<usize> = units.iter().filter_map(|u| u.hunk).collect();
+            patch.push_str(&file.patch_for(&hunks));
+        }
+        patch
+    }
+
+    /// 请求 AI 对改动分组
+    pub async fn propose_groups(&mut self, translator: &dyn ai_service::Translator) -> anyhow::Result<()> {
+        let content = (1..=self.units.len())
+            .map(|id| self.unit_text(id))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let response = translator.chat(SPLIT_PROMPT, &content).await?;
+        debug!("AI 返回的分组结果：\n{}", response);
+        let groups = parse_groups(&response)?;
+        self.groups = normalize_groups(groups, self.units.len());
+        Ok(())
+    }
+
+    pub fn print_groups(&self) {
+        for (i, group) in self.groups.iter().enumerate() {
+            println!("分组 {}: {}", i + 1, group.summary);
+            for id in &group.units {
+                println!("  {}", self.describe_unit(*id));
+            }
+        }
+    }
+
+    /// 交互式确认或编辑分组，返回 false 表示用户取消
+    pub fn confirm_groups(&mut self) -> anyhow::Result<bool> {
+        loop {
+            println!("\n拆分方案:");
+            println!("----------------------------------------");
+            self.print_groups();
+            println!("----------------------------------------");
+
+            let selection = prompt::select("是否使用此拆分方案？", &["确认分组", "编辑分组", "取消"], 0)?;
+
+            match selection {
+                0 => return Ok(true),
+                1 => self.edit_groups()?,
+                _ => return Ok(false),
+            }
+        }
+    }
+
+    fn edit_groups(&mut self) -> anyhow::Result<()> {
+        println!("请输入每个分组包含的改动编号（以逗号分隔），留空表示删除该分组，");
+        println!("未分配的改动将归入新的分组。");
+        let mut groups = Vec::new();
+        for (i, group) in self.groups.iter().enumerate() {
+            let initial = group.units.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            let input = prompt::input(&format!("分组 {} ({})", i + 1, group.summary), &initial)?;
+            groups.push(Group {
+                summary: group.summary.clone(),
+                units: parse_unit_list(&input),
+            });
+        }
+        self.groups = normalize_groups(groups, self.units.len());
+        Ok(())
+    }
+}
+
+/// 将文件拆分为可独立暂存的单元
+fn collect_units(files: &[FileDiff]) -> Vec<Unit> {
+    let mut units = Vec::new();
+    for (file_index, file) in files.iter().enumerate() {
+        if file.is_atomic() {
+            units.push(Unit { file: file_index, hunk: None });
+        } else {
+            for hunk_index in 0..file.hunks.len() {
+                units.push(Unit { file: file_index, hunk: Some(hunk_index) });
+            }
+        }
+    }
+    units
+}
+
+fn parse_unit_list(input: &str) -> Vec<usize> {
+    input.split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|s| s.trim().parse().ok())
+        .collect()
+}
+
+/// 从 AI 返回内容中提取 JSON 分组
+fn parse_groups(response: &str) -> anyhow::Result<Vec<Group>> {
+    let start = response.find('{');
+    let end = response.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => return Err(anyhow::anyhow!("AI 返回的分组结果格式无效")),
+    };
+    let parsed: GroupResponse = serde_json::from_str(json)
+        .map_err(|e| anyhow::anyhow!("解析 AI 返回的分组结果失败: {}", e))?;
+    Ok(parsed.groups)
+}
+
+/// 去除无效和重复的编号，未分配的单元归入新的分组
+fn normalize_groups(groups: Vec<Group>, unit_count: usize) -> Vec<Group> {
+    let mut assigned = vec![false; unit_count + 1];
+    let mut result = Vec::new();
+    for group in groups {
+        let mut units = Vec::new();
+        for id in group.units {
+            if id >= 1 && id <= unit_count && !assigned[id] {
+                assigned[id] = true;
+                units.push(id);
+            }
+        }
+        if !units.is_empty() {
+            units.sort_unstable();
+            result.push(Group { summary: group.summary, units });
+        }
+    }
+
+    let rest: Vec<usize> = (1..=unit_count).filter(|id| !assigned[*id]).collect();
+    if !rest.is_empty() {
+        result.push(Group { summary: String::from("其余改动"), units: rest });
+    }
+    result
+}
+
+/// 获取带 a/ b/ 前缀的完整暂存区补丁，便于之后通过 git apply 重新暂存
+pub fn get_staged_patch() -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--binary"])
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("执行 git diff 命令失败"));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// 取消暂存所有改动，工作区内容不变
+pub fn unstage_all() -> anyhow::Result<()> {
+    let head = Command::new("git")
+        .args(["rev-parse", "--verify", "-q", "HEAD"])
+        .output()?;
+    if !head.status.success() {
+        return Err(anyhow::anyhow!("当前仓库还没有任何提交，无法拆分提交"));
+    }
+    let status = Command::new("git")
+        .args(["reset", "-q"])
+        .status()?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("执行 git reset 命令失败"));
+    }
+    Ok(())
+}
+
+/// 通过 git apply --cached 将补丁应用到暂存区
+pub fn stage_patch(patch: &str) -> anyhow::Result<()> {
+    let mut child = Command::new("git")
+        .args(["apply", "--cached", "--whitespace=nowarn", "-"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child.stdin.take()
+        .ok_or_else(|| anyhow::anyhow!("无法写入 git apply 的标准输入"))?
+        .write_all(patch.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("执行 git apply --cached 失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_groups_with_code_fence() {
+        let response = "```json\n{\"groups\": [{\"summary\": \"fix\", \"hunks\": [1, 3]}, {\"summary\": \"docs\", \"hunks\": [2]}]}\n```";
+        let groups = parse_groups(response).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].units, vec![1, 3]);
+        assert_eq!(groups[1].summary, "docs");
+    }
+
+    #[test]
+    fn test_normalize_groups_assigns_rest() {
+        let groups = vec![
+            Group { summary: "a".into(), units: vec![2, 2, 9] },
+            Group { summary: "b".into(), units: vec![2] },
+        ];
+        let groups = normalize_groups(groups, 3);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].units, vec![2]);
+        assert_eq!(groups[1].units, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_patch_for_units() {
+        let diff = "diff --git a/a.txt b/a.txt
+index 1111111..2222222 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,2 @@
+-one
++ONE
+ two
+@@ -8,2 +8,2 @@
+ eight
+-nine
++NINE
+";
+        let plan = SplitPlan::new(diff);
+        assert_eq!(plan.units.len(), 2);
+        let patch = plan.patch_for(&[2]);
+        assert!(patch.contains("@@ -8,2 +8,2 @@"));
+        assert!(!patch.contains("+ONE"));
+        assert_eq!(parse_unit_list("1, 3 4"), vec![1, 3, 4]);
+    }
+}
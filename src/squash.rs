@@ -0,0 +1,102 @@
+// squash 模块
+// 收集提交范围内各提交的信息和合并后的 diff，用于生成 squash 合并的提交信息
+
+use crate::commit::CommitMessage;
+use crate::trailer::{self, Trailer};
+use std::process::Command;
+
+/// 待合并的提交范围
+pub struct SquashSource {
+    /// 各提交去掉 trailer 后的信息，按提交顺序排列
+    pub messages: Vec<String>,
+    /// 范围内所有提交合并后的改动
+    pub diff: String,
+    /// 去重后的 trailer，不含 Change-Id
+    pub trailers: Vec<Trailer>,
+}
+
+fn git(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "执行 git {} 失败: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 解析 "base..head" 格式的范围，省略 head 时使用 HEAD
+pub fn parse_range(range: &str) -> anyhow::Result<(String, String)> {
+    let (base, head) = range.split_once("..")
+        .ok_or_else(|| anyhow::anyhow!("范围格式应为 <base>..<head>，例如 main..feature"))?;
+    let head = head.strip_prefix('.').unwrap_or(head);
+    if base.is_empty() {
+        return Err(anyhow::anyhow!("范围中缺少 base，例如 main..feature"));
+    }
+    Ok((base.to_string(), if head.is_empty() { "HEAD" } else { head }.to_string()))
+}
+
+/// 收集范围内的提交信息和合并后的 diff
+pub fn collect(range: &str) -> anyhow::Result<SquashSource> {
+    let (base, head) = parse_range(range)?;
+    let log = git(&["log", "--reverse", "--no-merges", "--format=%B%x00", &format!("{}..{}", base, head)])?;
+    let raw_messages: Vec<&str> = log.split('\0')
+        .map(|m| m.trim())
+        .filter(|m| !m.is_empty())
+        .collect();
+    if raw_messages.is_empty() {
+        return Err(anyhow::anyhow!("范围 {}..{} 中没有需要合并的提交", base, head));
+    }
+
+    // 与 git merge --squash 一致，使用 base 与 head 的分叉点到 head 之间的改动
+    let diff = git(&["diff", "--no-prefix", &format!("{}...{}", base, head)])?;
+    let (messages, trailers) = merge_trailers(&raw_messages);
+    Ok(SquashSource { messages, diff, trailers })
+}
+
+/// 拆出各提交的 trailer 并去重，丢弃 Change-Id（合并后的提交需要新的 Change-Id）
+fn merge_trailers(raw_messages: &[&str]) -> (Vec<String>, Vec<Trailer>) {
+    let mut trailers = Vec::new();
+    let messages = raw_messages.iter()
+        .map(|raw| {
+            let message = CommitMessage::parse(raw);
+            for t in message.trailers.iter().filter(|t| !t.is("Change-Id")) {
+                trailer::add(&mut trailers, t.clone());
+            }
+            CommitMessage { trailers: Vec::new(), ..message }.format()
+        })
+        .collect();
+    trailer::sort(&mut trailers);
+    (messages, trailers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("main..feature").unwrap(), ("main".to_string(), "feature".to_string()));
+        assert_eq!(parse_range("main...feature").unwrap(), ("main".to_string(), "feature".to_string()));
+        assert_eq!(parse_range("origin/main..").unwrap(), ("origin/main".to_string(), "HEAD".to_string()));
+        assert!(parse_range("main").is_err());
+        assert!(parse_range("..feature").is_err());
+    }
+
+    #[test]
+    fn test_merge_trailers() {
+        let (messages, trailers) = merge_trailers(&[
+            "feat: add a\n\nbody a\n\nFixes: #1\nCo-authored-by: B <b@c.d>\nChange-Id: I111",
+            "fix: fix a\n\nFixes: #1\nPMS: BUG-2\nSigned-off-by: A <a@b.c>\nChange-Id: I222",
+        ]);
+        assert_eq!(messages, vec!["feat: add a\n\nbody a", "fix: fix a"]);
+        assert_eq!(trailers, vec![
+            Trailer::new("Fixes", "#1"),
+            Trailer::new("Co-authored-by", "B <b@c.d>"),
+            Trailer::new("PMS", "BUG-2"),
+            Trailer::new("Signed-off-by", "A <a@b.c>"),
+        ]);
+    }
+}
@@ -63,6 +63,7 @@ pub fn print_progress(msg: &str, percent: Option<u8>) {
     };
     // \r回到行首，补足空格清除残留
     let text = format!("\r{}{}{}", msg, progress, "      ");
-    print!("{}", text);
-    io::stdout().flush().ok();
+    // 输出到标准错误，避免混入 commit --dry-run 等命令的标准输出
+    eprint!("{}", text);
+    io::stderr().flush().ok();
 }
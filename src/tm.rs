@@ -0,0 +1,332 @@
+// 翻译记忆模块
+// 以规范化后的原文片段（标题、正文的每一行、Log 行）为键保存历史译文：
+// 所有片段都完全匹配时直接复用译文，不再请求 AI；相似的片段作为参考译文加入翻译提示词，
+// 使相同内容的译法保持一致
+
+use crate::cache;
+use crate::config::{Config, TranslationMemoryConfig};
+use crate::language::LanguagePair;
+use log::{debug, warn};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// 翻译记忆文件，位于缓存目录
+const MEMORY_FILE: &str = "translation-memory.json";
+
+/// 提示词中参考译文的最大条数
+const MAX_REFERENCES: usize = 5;
+
+/// 一条翻译记忆：翻译方向、规范化后的原文和译文（均不含行首前缀）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    source_language: String,
+    target_language: String,
+    source: String,
+    target: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Memory {
+    entries: Vec<Entry>,
+    settings: TranslationMemoryConfig,
+    path: Option<PathBuf>,
+}
+
+/// 行首不需要翻译的前缀：列表标记、提交类型前缀和 Log/Influence 字段名
+fn prefix_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(concat!(
+        r"^\s*(?:[-*+•]\s+|\d{1,3}[.)]\s+|\d{1,3}、\s*",
+        r"|[a-z]+(?:\([^)]*\))?!?:\s+|(?:Log|Influence):\s*)?",
+    )).unwrap())
+}
+
+/// 拆分行首前缀和需要翻译的内容
+fn split_prefix(line: &str) -> (&str, &str) {
+    let end = prefix_regex().find(line).map(|m| m.end()).unwrap_or(0);
+    (&line[..end], &line[end..])
+}
+
+/// 规范化原文片段：合并空白、去掉句末标点并统一为小写
+fn normalize(segment: &str) -> String {
+    segment.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['。', '．', '.', '！', '!', '；', ';', '，', ','])
+        .to_lowercase()
+}
+
+/// 按行首前缀调整译文首字母大小写：提交类型前缀后小写，其他行大写
+fn adapt_case(prefix: &str, target: &str) -> String {
+    let is_title = prefix.trim_end().ends_with(':') && !prefix.starts_with("Log") && !prefix.starts_with("Influence");
+    let mut chars = target.chars();
+    match chars.next() {
+        Some(first) if is_title => first.to_lowercase().chain(chars).collect(),
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// 不含任何文字（如分隔行、纯数字）的片段无需翻译
+fn is_translatable(segment: &str) -> bool {
+    segment.chars().any(char::is_alphabetic)
+}
+
+/// 按字符计算的编辑距离相似度，范围 0 到 1
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+/// 当前进程使用的翻译记忆，首次使用时加载
+pub fn current() -> &'static Mutex<Memory> {
+    static MEMORY: OnceLock<Mutex<Memory>> = OnceLock::new();
+    MEMORY.get_or_init(|| Mutex::new(Memory::load()))
+}
+
+/// 从翻译记忆中取得完整译文，任一片段没有完全匹配的记忆时返回 None
+pub fn recall(text: &str, direction: &LanguagePair) -> Option<String> {
+    current().lock().ok()?.translate(text, direction)
+}
+
+/// 记录一次翻译的结果，原文和译文的行无法一一对应时不记录
+pub fn remember(text: &str, translated: &str, direction: &LanguagePair) {
+    let Ok(mut memory) = current().lock() else {
+        return;
+    };
+    if memory.record(text, translated, direction) {
+        if let Err(e) = memory.save() {
+            warn!("保存翻译记忆失败: {}", e);
+        }
+    }
+}
+
+impl Memory {
+    /// 按配置加载翻译记忆，未启用时为空
+    pub fn load() -> Self {
+        let settings = Config::load().map(|config| config.translation_memory).unwrap_or_default();
+        if !settings.enabled {
+            return Memory { settings, ..Default::default() };
+        }
+        let path = cache::cache_dir().map(|dir| dir.join(MEMORY_FILE));
+        let entries = path.as_ref()
+            .filter(|path| path.exists())
+            .and_then(|path| {
+                let content = std::fs::read_to_string(path).ok()?;
+                match serde_json::from_str::<Vec<Entry>>(&content) {
+                    Ok(entries) => Some(entries),
+                    Err(e) => {
+                        warn!("读取翻译记忆 {} 失败: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .unwrap_or_default();
+        debug!("已加载翻译记忆，共 {} 条", entries.len());
+        Memory { entries, settings, path }
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+    fn matches(entry: &Entry, direction: &LanguagePair) -> bool {
+        entry.source_language == direction.source && entry.target_language == direction.target
+    }
+
+    /// 完全匹配的译文，相同原文有多条记忆时使用最近的一条
+    fn lookup(&self, key: &str, direction: &LanguagePair) -> Option<&str> {
+        self.entries.iter()
+            .rev()
+            .find(|entry| Self::matches(entry, direction) && entry.source == key)
+            .map(|entry| entry.target.as_str())
+    }
+
+    /// 逐行从记忆中组合译文，行首前缀保持不变
+    pub fn translate(&self, text: &str, direction: &LanguagePair) -> Option<String> {
+        if !self.settings.enabled || self.entries.is_empty() {
+            return None;
+        }
+        let mut hits = 0;
+        let mut lines = Vec::new();
+        for line in text.trim().lines() {
+            let (prefix, segment) = split_prefix(line);
+            if !is_translatable(segment) {
+                lines.push(line.trim_end().to_string());
+                continue;
+            }
+            let target = self.lookup(&normalize(segment), direction)?;
+            lines.push(format!("{}{}", prefix, adapt_case(prefix, target)));
+            hits += 1;
+        }
+        (hits > 0).then(|| lines.join("\n"))
+    }
+
+    /// 记录原文和译文中一一对应的片段，返回记忆是否有变化
+    pub fn record(&mut self, text: &str, translated: &str, direction: &LanguagePair) -> bool {
+        if !self.settings.enabled {
+            return false;
+        }
+        let sources: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+        let targets: Vec<&str> = translated.lines().filter(|line| !line.trim().is_empty()).collect();
+        if sources.len() != targets.len() {
+            debug!("原文和译文的行数不一致，不记录翻译记忆");
+            return false;
+        }
+
+        let mut changed = false;
+        for (source, target) in sources.into_iter().zip(targets) {
+            let (source_prefix, source_segment) = split_prefix(source);
+            let (target_prefix, target_segment) = split_prefix(target);
+            let key = normalize(source_segment);
+            let target_segment = target_segment.trim();
+            // 前缀不同说明行没有对齐，译文与原文相同说明没有翻译
+            if source_prefix.trim() != target_prefix.trim()
+                || !is_translatable(source_segment)
+                || key == normalize(target_segment)
+            {
+                continue;
+            }
+            // 只有首字母大小写不同时视为同一译文
+            if self.lookup(&key, direction).is_some_and(|existing| adapt_case("", existing) == adapt_case("", target_segment)) {
+                continue;
+            }
+            self.entries.retain(|entry| !(Self::matches(entry, direction) && entry.source == key));
+            self.entries.push(Entry {
+                source_language: direction.source.clone(),
+                target_language: direction.target.clone(),
+                source: key,
+                target: target_segment.to_string(),
+            });
+            changed = true;
+        }
+
+        let max_entries = self.settings.max_entries;
+        if self.entries.len() > max_entries {
+            self.entries.drain(..self.entries.len() - max_entries);
+        }
+        changed
+    }
+
+    /// 与原文中未完全匹配的片段相似的记忆，按相似度从高到低排列
+    fn references(&self, text: &str, direction: &LanguagePair) -> Vec<&Entry> {
+        let mut candidates: Vec<(f64, &Entry)> = Vec::new();
+        for line in text.lines() {
+            let (_, segment) = split_prefix(line);
+            if !is_translatable(segment) {
+                continue;
+            }
+            let key = normalize(segment);
+            if self.lookup(&key, direction).is_some() {
+                continue;
+            }
+            let best = self.entries.iter()
+                .filter(|entry| Self::matches(entry, direction))
+                .map(|entry| (similarity(&key, &entry.source), entry))
+                .filter(|(score, _)| *score >= self.settings.fuzzy_threshold)
+                .max_by(|a, b| a.0.total_cmp(&b.0));
+            if let Some((score, entry)) = best {
+                if !candidates.iter().any(|(_, existing)| existing.source == entry.source) {
+                    candidates.push((score, entry));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.into_iter().take(MAX_REFERENCES).map(|(_, entry)| entry).collect()
+    }
+
+    /// 翻译提示词中的参考译文，没有相似的记忆时返回 None
+    pub fn reference_prompt(&self, text: &str, direction: &LanguagePair) -> Option<String> {
+        if !self.settings.enabled {
+            return None;
+        }
+        let lines: Vec<String> = self.references(text, direction)
+            .iter()
+            .map(|entry| format!("- \"{}\" → \"{}\"", entry.source, entry.target))
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Reference translations of similar sentences (reuse their wording where it fits):\n{}",
+            lines.join("\n")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory() -> Memory {
+        Memory { settings: TranslationMemoryConfig::default(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_split_and_normalize() {
+        assert_eq!(split_prefix("fix(dock): 修复崩溃问题"), ("fix(dock): ", "修复崩溃问题"));
+        assert_eq!(split_prefix("2. 优化性能"), ("2. ", "优化性能"));
+        assert_eq!(split_prefix("Log: 修复崩溃问题"), ("Log: ", "修复崩溃问题"));
+        assert_eq!(split_prefix("修复崩溃问题"), ("", "修复崩溃问题"));
+        assert_eq!(normalize("  修复崩溃问题。"), "修复崩溃问题");
+        assert_eq!(normalize("Fix  the Crash."), "fix the crash");
+    }
+
+    #[test]
+    fn test_record_and_reuse() {
+        let mut memory = memory();
+        let direction = LanguagePair::new("zh", "en");
+        let source = "fix: 修复崩溃问题\n\n1. 优化性能\n\nLog: 修复崩溃问题";
+        let translated = "fix: fix crash issue\n\n1. Optimize performance\n\nLog: Fix crash issue";
+        assert!(memory.record(source, translated, &direction));
+        assert_eq!(memory.entries.len(), 2);
+        assert!(!memory.record("fix: 修复崩溃问题", "fix: fix crash issue", &direction));
+        assert!(!memory.record(source, translated, &direction));
+
+        assert_eq!(
+            memory.translate("feat: 优化性能\n\n- 修复崩溃问题。", &direction).unwrap(),
+            "feat: optimize performance\n\n- Fix crash issue"
+        );
+        assert_eq!(memory.translate("fix: 修复登录问题", &direction), None);
+        assert_eq!(memory.translate("fix: 修复崩溃问题", &LanguagePair::new("zh", "ja")), None);
+
+        // 行数不一致时不记录
+        assert!(!memory.record("fix: 修复登录问题\n\n1. 检查空值", "fix: fix login", &direction));
+    }
+
+    #[test]
+    fn test_fuzzy_references() {
+        let mut memory = memory();
+        let direction = LanguagePair::new("zh", "en");
+        memory.record("修复任务栏图标显示错位的问题", "Fix misaligned taskbar icons", &direction);
+        memory.record("优化性能", "Optimize performance", &direction);
+
+        let prompt = memory.reference_prompt("fix: 修复任务栏图标显示错位问题", &direction).unwrap();
+        assert!(prompt.contains("\"修复任务栏图标显示错位的问题\" → \"Fix misaligned taskbar icons\""));
+        assert!(!prompt.contains("Optimize performance"));
+        assert_eq!(memory.reference_prompt("fix: 修复登录问题", &direction), None);
+    }
+}
@@ -0,0 +1,244 @@
+// trailer 模块
+// 提交信息末尾的 trailer 字段（如 Signed-off-by、Co-authored-by、Change-Id），
+// 与 git interpret-trailers 一致，只把最后一段识别为 trailer
+
+use log::debug;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::process::Command;
+
+/// 一个 trailer 字段
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
+}
+
+impl Trailer {
+    pub fn new(key: &str, value: &str) -> Self {
+        Trailer {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// 解析 "Key: value" 格式的行
+    /// 与 git 不同，要求字段名以大写字母开头，避免把双语提交信息中的 "feat: 中文标题" 误认为 trailer
+    pub fn parse(line: &str) -> Option<Self> {
+        let regex = Regex::new(r"^([A-Z][A-Za-z0-9-]*):\s*(\S.*)$").unwrap();
+        regex.captures(line.trim_end())
+            .map(|c| Trailer::new(&c[1], c[2].trim_end()))
+    }
+
+    /// 字段名是否相同（不区分大小写）
+    pub fn is(&self, key: &str) -> bool {
+        self.key.eq_ignore_ascii_case(key)
+    }
+
+    /// 排序权重：Signed-off-by 位于其他字段之后，Change-Id 始终在最后
+    fn rank(&self) -> u8 {
+        if self.is("Change-Id") {
+            2
+        } else if self.is("Signed-off-by") {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+impl fmt::Display for Trailer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.value)
+    }
+}
+
+/// 将提交信息拆分为正文和 trailer 列表，只有全部由 trailer 组成的最后一段才被识别，
+/// 以空白开头的行视为上一个字段的续行；标题所在的第一段和只有 Log 字段的段落不会被识别为 trailer
+pub fn split(message: &str) -> (String, Vec<Trailer>) {
+    let message = message.trim_end();
+    let Some((body, last)) = message.rsplit_once("\n\n") else {
+        return (message.to_string(), Vec::new());
+    };
+
+    let mut trailers: Vec<Trailer> = Vec::new();
+    for line in last.lines() {
+        if line.starts_with([' ', '\t']) {
+            match trailers.last_mut() {
+                Some(trailer) => {
+                    trailer.value.push('\n');
+                    trailer.value.push_str(line);
+                }
+                None => return (message.to_string(), Vec::new()),
+            }
+        } else if let Some(trailer) = Trailer::parse(line) {
+            trailers.push(trailer);
+        } else {
+            return (message.to_string(), Vec::new());
+        }
+    }
+
+    if trailers.iter().all(|t| t.is("Log")) {
+        return (message.to_string(), Vec::new());
+    }
+    (body.trim_end().to_string(), trailers)
+}
+
+/// 将正文和 trailer 列表重新组合为提交信息
+pub fn join(body: &str, trailers: &[Trailer]) -> String {
+    let body = body.trim_end();
+    if trailers.is_empty() {
+        return body.to_string();
+    }
+    let trailers = trailers.iter().map(|t| t.to_string()).collect::<Vec<_>>().join("\n");
+    if body.is_empty() {
+        trailers
+    } else {
+        format!("{}\n\n{}", body, trailers)
+    }
+}
+
+/// 添加 trailer，已存在相同字段名和值的 trailer 时忽略（对应 git 的 addIfDifferent）
+pub fn add(trailers: &mut Vec<Trailer>, trailer: Trailer) {
+    if !trailers.iter().any(|t| t.is(&trailer.key) && t.value == trailer.value) {
+        trailers.push(trailer);
+    }
+}
+
+/// 按排序规则调整顺序，同级字段保持原有顺序
+pub fn sort(trailers: &mut [Trailer]) {
+    trailers.sort_by_key(Trailer::rank);
+}
+
+/// 向提交信息中添加 trailer，并按规则去重、排序
+pub fn apply(message: &str, extra: impl IntoIterator<Item = Trailer>) -> String {
+    let (body, mut trailers) = split(message);
+    for trailer in extra {
+        add(&mut trailers, trailer);
+    }
+    sort(&mut trailers);
+    join(&body, &trailers)
+}
+
+fn git_config(key: &str) -> Option<String> {
+    let output = Command::new("git").args(["config", key]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// 当前用户的 Signed-off-by 字段
+pub fn signoff() -> anyhow::Result<Trailer> {
+    let name = git_config("user.name")
+        .ok_or_else(|| anyhow::anyhow!("未设置 git user.name，无法添加 Signed-off-by"))?;
+    let email = git_config("user.email")
+        .ok_or_else(|| anyhow::anyhow!("未设置 git user.email，无法添加 Signed-off-by"))?;
+    Ok(Trailer::new("Signed-off-by", &format!("{} <{}>", name, email)))
+}
+
+/// 将合作者别名解析为 "Name <email>"：
+/// 已是该格式时直接使用，否则依次查找配置中的别名和仓库的 .mailmap
+pub fn resolve_co_author(alias: &str, aliases: &BTreeMap<String, String>) -> anyhow::Result<Trailer> {
+    let ident_regex = Regex::new(r"^.+<[^<>@\s]+@[^<>\s]+>$").unwrap();
+    // 优先完全匹配，其次按名称顺序取第一个忽略大小写匹配的别名
+    let ident = if ident_regex.is_match(alias.trim()) {
+        alias.trim().to_string()
+    } else if let Some(ident) = aliases.get(alias).or_else(|| aliases.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(alias))
+        .map(|(_, ident)| ident))
+    {
+        ident.clone()
+    } else {
+        let mailmap = read_mailmap().unwrap_or_default();
+        find_in_mailmap(&mailmap, alias)
+            .ok_or_else(|| anyhow::anyhow!("无法解析合作者 {}，请在配置的 trailers.co_authors 或 .mailmap 中添加", alias))?
+    };
+    debug!("合作者 {} 解析为 {}", alias, ident);
+    Ok(Trailer::new("Co-authored-by", &ident))
+}
+
+fn read_mailmap() -> Option<String> {
+    let path = match git_config("mailmap.file") {
+        Some(path) => path,
+        None => {
+            let output = Command::new("git").args(["rev-parse", "--show-toplevel"]).output().ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            format!("{}/.mailmap", String::from_utf8_lossy(&output.stdout).trim())
+        }
+    };
+    std::fs::read_to_string(path).ok()
+}
+
+/// 在 .mailmap 中按姓名或邮箱用户名查找，返回规范的 "Name <email>"
+fn find_in_mailmap(mailmap: &str, alias: &str) -> Option<String> {
+    let entry_regex = Regex::new(r"^([^<#]*?)\s*<([^>]+)>").unwrap();
+    let alias = alias.to_lowercase();
+    mailmap.lines()
+        .filter_map(|line| entry_regex.captures(line.trim()))
+        .find(|c| {
+            let name = c[1].trim().to_lowercase();
+            let user = c[2].split('@').next().unwrap_or_default().to_lowercase();
+            !name.is_empty() && (name == alias || user == alias)
+        })
+        .map(|c| format!("{} <{}>", c[1].trim(), &c[2]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_only_last_paragraph() {
+        let message = "feat: add x\n\nLog: not a trailer here\nmore text\n\nFixes: #1\nSigned-off-by: A <a@b.c>\n";
+        let (body, trailers) = split(message);
+        assert_eq!(body, "feat: add x\n\nLog: not a trailer here\nmore text");
+        assert_eq!(trailers, vec![Trailer::new("Fixes", "#1"), Trailer::new("Signed-off-by", "A <a@b.c>")]);
+
+        let (body, trailers) = split("feat: add x\n\nfeat: 添加 x");
+        assert_eq!(body, "feat: add x\n\nfeat: 添加 x");
+        assert!(trailers.is_empty());
+
+        let (_, trailers) = split("Fixes: #1");
+        assert!(trailers.is_empty());
+
+        let (body, trailers) = split("fix: x\n\nLog: Fixed x");
+        assert_eq!(body, "fix: x\n\nLog: Fixed x");
+        assert!(trailers.is_empty());
+    }
+
+    #[test]
+    fn test_apply_dedup_and_order() {
+        let message = "fix: x\n\nbody\n\nChange-Id: I1\nSigned-off-by: A <a@b.c>\nFixes: #1";
+        let result = apply(message, vec![
+            Trailer::new("Fixes", "#1"),
+            Trailer::new("Co-authored-by", "B <b@c.d>"),
+            Trailer::new("Signed-off-by", "A <a@b.c>"),
+        ]);
+        assert_eq!(result, "fix: x\n\nbody\n\nFixes: #1\nCo-authored-by: B <b@c.d>\nSigned-off-by: A <a@b.c>\nChange-Id: I1");
+    }
+
+    #[test]
+    fn test_resolve_co_author() {
+        let aliases = BTreeMap::from([("bob".to_string(), "Bob Smith <bob@example.com>".to_string())]);
+        assert_eq!(resolve_co_author("Bob", &aliases).unwrap().value, "Bob Smith <bob@example.com>");
+        assert_eq!(resolve_co_author("C D <c@d.e>", &aliases).unwrap().value, "C D <c@d.e>");
+
+        let aliases = BTreeMap::from([
+            ("Bob".to_string(), "Bob Smith <bob@example.com>".to_string()),
+            ("bob".to_string(), "Bob Lee <lee@example.com>".to_string()),
+        ]);
+        assert_eq!(resolve_co_author("bob", &aliases).unwrap().value, "Bob Lee <lee@example.com>");
+        assert_eq!(resolve_co_author("BOB", &aliases).unwrap().value, "Bob Smith <bob@example.com>");
+
+        let mailmap = "# comment\nAlice Wang <alice@example.com> <alice@old.com>\n<carol@example.com> <c@old.com>\n";
+        assert_eq!(find_in_mailmap(mailmap, "alice").as_deref(), Some("Alice Wang <alice@example.com>"));
+        assert_eq!(find_in_mailmap(mailmap, "alice wang").as_deref(), Some("Alice Wang <alice@example.com>"));
+        assert_eq!(find_in_mailmap(mailmap, "carol"), None);
+    }
+}
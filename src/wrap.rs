@@ -0,0 +1,127 @@
+// 换行模块
+// 按 Unicode 显示宽度（CJK 字符占两列）和 Unicode 断行规则折行提交信息，
+// 保留列表标记后的悬挂缩进，不拆分 URL，标题、缩进的代码和 trailer 保持原样
+
+use crate::trailer;
+use regex::Regex;
+use std::sync::OnceLock;
+use textwrap::core::{display_width, Word};
+use textwrap::{Options, WordSeparator};
+
+/// 提交信息正文每行的最大显示宽度
+pub const MAX_LINE_WIDTH: usize = 72;
+
+fn url_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"[a-z][a-z0-9+.-]*://\S+").unwrap())
+}
+
+/// 列表标记：-、*、+、•、1.、1)、1、
+fn list_marker_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^\s*(?:[-*+•]\s+|\d{1,3}[.)]\s+|\d{1,3}、\s*)").unwrap())
+}
+
+/// 标题格式的行，如双语提交信息中另一种语言的标题
+fn title_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^[a-z]+(?:\([^)]*\))?!?:\s*\S").unwrap())
+}
+
+/// 按 Unicode 断行规则拆分单词，URL 整体作为一个单词
+fn find_words(line: &str) -> Box<dyn Iterator<Item = Word<'_>> + '_> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    for url in url_regex().find_iter(line) {
+        words.extend(WordSeparator::UnicodeBreakProperties.find_words(&line[start..url.start()]));
+        let end = url.end() + (line[url.end()..].len() - line[url.end()..].trim_start_matches(' ').len());
+        words.push(Word::from(&line[url.start()..end]));
+        start = end;
+    }
+    words.extend(WordSeparator::UnicodeBreakProperties.find_words(&line[start..]));
+    Box::new(words.into_iter())
+}
+
+/// 折行单行文本：列表项的后续行缩进到列表标记之后，缩进的代码行和不超宽的行保持原样
+pub fn wrap_line(line: &str, width: usize) -> String {
+    if display_width(line) <= width {
+        return line.to_string();
+    }
+    let marker = list_marker_regex().find(line).map(|m| m.as_str()).unwrap_or_default();
+    if marker.is_empty() && (line.starts_with('\t') || line.starts_with("    ")) {
+        return line.to_string();
+    }
+
+    let indent = " ".repeat(display_width(marker));
+    let options = Options::new(width)
+        .subsequent_indent(&indent)
+        .break_words(false)
+        .word_separator(WordSeparator::Custom(find_words));
+    textwrap::fill(line, options)
+}
+
+/// 逐行折行文本
+pub fn wrap_text(text: &str, width: usize) -> String {
+    text.lines()
+        .map(|line| wrap_line(line, width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 折行完整的提交信息：标题、空行后的标题格式行（双语提交信息中的另一种语言标题）和 trailer 不折行
+pub fn wrap_message(message: &str, width: usize) -> String {
+    let (text, trailers) = trailer::split(message);
+    let mut previous_blank = false;
+    let lines: Vec<String> = text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let keep = i == 0 || (previous_blank && title_regex().is_match(line));
+            previous_blank = line.trim().is_empty();
+            if keep {
+                line.to_string()
+            } else {
+                wrap_line(line, width)
+            }
+        })
+        .collect();
+    trailer::join(&lines.join("\n"), &trailers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_cjk_and_list_indent() {
+        let line = "1. 这是一个很长的中文句子，用于测试换行是否正确处理中文字符宽度和标点符号。";
+        let wrapped = wrap_line(line, 30);
+        assert_eq!(wrapped, "1. 这是一个很长的中文句子，用\n   于测试换行是否正确处理中文\n   字符宽度和标点符号。");
+        assert!(wrapped.lines().all(|l| display_width(l) <= 30));
+
+        let wrapped = wrap_line("- fix the parser so that it handles empty input", 20);
+        assert_eq!(wrapped, "- fix the parser so\n  that it handles\n  empty input");
+    }
+
+    #[test]
+    fn test_wrap_keeps_urls_and_code() {
+        let url = "https://example.com/some/very/long/path/that/must/stay/whole";
+        let wrapped = wrap_line(&format!("see {} for details", url), 30);
+        assert_eq!(wrapped, format!("see\n{}\nfor details", url));
+
+        let code = "    let value = some_function_with_a_long_name(argument_one, argument_two);";
+        assert_eq!(wrap_line(code, 30), code);
+    }
+
+    #[test]
+    fn test_wrap_message() {
+        let long = "fix the parser so that it handles empty input without crashing";
+        let message = format!(
+            "fix: {}\n\n{}\n\nfix: 修复解析器在输入为空时崩溃的问题并补充对应的测试用例\n\nSigned-off-by: Some Very Long Name <some.very.long.name@example.com>",
+            long, long
+        );
+        assert_eq!(wrap_message(&message, 40), format!(
+            "fix: {}\n\nfix the parser so that it handles empty\ninput without crashing\n\nfix: 修复解析器在输入为空时崩溃的问题并补充对应的测试用例\n\nSigned-off-by: Some Very Long Name <some.very.long.name@example.com>",
+            long
+        ));
+    }
+}